@@ -0,0 +1,133 @@
+use crate::{
+    game_object::{GameObject, Member},
+    interpreter::Interpreter,
+    lexer::{Lexer, Spanned, Token},
+    line_editor::LineEditor,
+    parser::{Expression, Instruction, Parser, Statement},
+};
+
+#[derive(Clone)]
+pub struct Candidate {
+    /// What gets written in the command.
+    pub text: String,
+    /// What is shown in the list of candidates, e.g. a method's signature.
+    pub label: String,
+}
+
+impl Candidate {
+    fn from_member(member: &Member) -> Self {
+        Self {
+            // Methods are completed with their opening paren so the arguments can be typed
+            // right away
+            text: if member.is_callable() {
+                format!("{}(", member.name)
+            } else {
+                member.name.clone()
+            },
+            label: member.signature(),
+        }
+    }
+}
+
+/// Tab completion of the word under the cursor, cycling through the candidates on each press.
+#[derive(Clone)]
+pub struct Completion {
+    pub candidates: Vec<Candidate>,
+    pub index: usize,
+    /// Byte range of the command currently filled by the selected candidate.
+    start: usize,
+    end: usize,
+}
+
+impl Completion {
+    pub fn new(
+        command: &str,
+        cursor: usize,
+        root: &mut dyn GameObject,
+        interpreter: &Interpreter,
+    ) -> Option<Self> {
+        let start = command[..cursor]
+            .char_indices()
+            .rev()
+            .take_while(|(_, char)| char.is_alphanumeric() || *char == '_')
+            .last()
+            .map_or(cursor, |(idx, _)| idx);
+        let prefix = &command[start..cursor];
+
+        let members = match command[..start].strip_suffix('.') {
+            Some(object_path) => {
+                let instruction = Self::object_path(object_path)?;
+                interpreter.object(root, instruction).ok()?.members()
+            }
+            None => [root.members(), interpreter.globals()].concat(),
+        };
+
+        let candidates: Vec<Candidate> = members
+            .iter()
+            .filter(|member| member.name.starts_with(prefix))
+            .map(Candidate::from_member)
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        Some(Self {
+            candidates,
+            index: 0,
+            start,
+            end: cursor,
+        })
+    }
+
+    /// Parses the object path right before a `.`, only accepting literal arguments so that
+    /// completing never runs a method.
+    pub fn object_path(source: &str) -> Option<Instruction> {
+        let tokens = Lexer::tokenize(source).ok()?;
+
+        let mut idx = tokens.len();
+        loop {
+            if let Some(Token::CloseParen) = tokens.get(idx.checked_sub(1)?).map(|t| &t.node) {
+                let mut depth = 0;
+                loop {
+                    idx = idx.checked_sub(1)?;
+                    match tokens[idx].node {
+                        Token::CloseParen => depth += 1,
+                        Token::OpenParen => depth -= 1,
+                        Token::Keyword(_) => return None,
+                        _ => (),
+                    }
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            idx = idx.checked_sub(1)?;
+            let Token::Keyword(_) = tokens[idx].node else {
+                return None;
+            };
+            match idx.checked_sub(1).map(|previous| &tokens[previous].node) {
+                Some(Token::Dot) => idx -= 1,
+                _ => break,
+            }
+        }
+
+        let path: Vec<Spanned<Token>> = tokens[idx..].to_vec();
+        match Parser::parse(path).ok()?.pop()? {
+            Statement::Expression(Spanned {
+                node: Expression::Instruction(instruction),
+                ..
+            }) => Some(instruction),
+            _ => None,
+        }
+    }
+
+    pub fn next(&mut self) {
+        self.index = (self.index + 1) % self.candidates.len();
+    }
+
+    /// Writes the selected candidate in place of the previous one, moving the cursor after it.
+    pub fn apply(&mut self, editor: &mut LineEditor) {
+        let text = &self.candidates[self.index].text;
+        editor.replace_range(self.start..self.end, text);
+        self.end = self.start + text.len();
+    }
+}
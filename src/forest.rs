@@ -0,0 +1,152 @@
+use crate::game_object::{
+    arg_count, int_arg, GameObject, HandleError, Member, Param, Segment, StatusField, Value,
+};
+use serde::{Deserialize, Serialize};
+
+const PLOT_COUNT: usize = 3;
+/// Seconds a plot takes to grow a tree.
+const GROWTH_TIME: i64 = 10;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Plot {
+    growth: i64,
+    logs: i64,
+}
+
+impl Plot {
+    fn is_grown(&self) -> bool {
+        self.growth >= GROWTH_TIME
+    }
+
+    /// Seconds left until the tree is grown, `None` if it already is.
+    fn time_left(&self) -> Option<i64> {
+        Some(GROWTH_TIME - self.growth).filter(|left| *left > 0)
+    }
+
+    fn tick(&mut self) {
+        if !self.is_grown() {
+            self.growth += 1;
+        }
+    }
+
+    fn harvest(&mut self) -> Result<i64, HandleError> {
+        if !self.is_grown() {
+            return Err(HandleError::Failed(
+                "This tree is not grown yet".to_string(),
+            ));
+        }
+        self.growth = 0;
+        self.logs += 1;
+        Ok(1)
+    }
+}
+
+impl GameObject for Plot {
+    fn class_name(&self) -> String {
+        "Plot".to_string()
+    }
+
+    fn members(&self) -> Vec<Member> {
+        vec![
+            Member::property("growth", "Seconds the tree has been growing"),
+            Member::property("grown", "Whether the tree can be harvested"),
+            Member::property("logs", "Logs harvested from this plot"),
+            Member::method(
+                "harvest",
+                vec![],
+                "Cuts the grown tree down for a log, a new one starts growing",
+            ),
+        ]
+    }
+
+    fn handle(&mut self, segment: Segment) -> Result<Value, HandleError> {
+        match segment {
+            Segment::Access(key) if key == "growth" => Ok(Value::IntValue(self.growth)),
+            Segment::Access(key) if key == "grown" => Ok(Value::BoolValue(self.is_grown())),
+            Segment::Access(key) if key == "logs" => Ok(Value::IntValue(self.logs)),
+            Segment::FunctionCall(name, args) if name == "harvest" => {
+                arg_count(&args, 0)?;
+                Ok(Value::IntValue(self.harvest()?))
+            }
+            _ => self.return_err(segment),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Forest {
+    plots: Vec<Plot>,
+}
+
+impl Default for Forest {
+    fn default() -> Self {
+        Self {
+            plots: vec![Plot::default(); PLOT_COUNT],
+        }
+    }
+}
+
+impl Forest {
+    pub fn logs(&self) -> i64 {
+        self.plots.iter().map(|plot| plot.logs).sum()
+    }
+
+    pub fn tick(&mut self) {
+        self.plots.iter_mut().for_each(Plot::tick);
+    }
+}
+
+impl GameObject for Forest {
+    fn class_name(&self) -> String {
+        "Forest".to_string()
+    }
+
+    fn members(&self) -> Vec<Member> {
+        vec![
+            Member::property("logs", "Logs harvested from every plot"),
+            Member::property("plots", "Number of plots"),
+            Member::child(
+                "plot",
+                Some(vec![Param::new("idx", "Int")]),
+                "The plot at the given index, starting at 0",
+            ),
+        ]
+    }
+
+    fn handle(&mut self, segment: Segment) -> Result<Value, HandleError> {
+        match segment {
+            Segment::Access(key) if key == "logs" => Ok(Value::IntValue(self.logs())),
+            Segment::Access(key) if key == "plots" => Ok(Value::IntValue(self.plots.len() as i64)),
+            _ => self.return_err(segment),
+        }
+    }
+
+    fn child(&mut self, segment: Segment) -> Result<&mut dyn GameObject, HandleError> {
+        match segment {
+            Segment::FunctionCall(name, args) if name == "plot" => {
+                arg_count(&args, 1)?;
+                let idx = int_arg(&args, 0)?;
+                let count = self.plots.len();
+                usize::try_from(idx)
+                    .ok()
+                    .and_then(|idx| self.plots.get_mut(idx))
+                    .map(|plot| plot as &mut dyn GameObject)
+                    .ok_or(HandleError::NotFound(format!(
+                        "Did not find plot {} on Forest, there are {} plots",
+                        idx, count
+                    )))
+            }
+            _ => Err(self.not_found(segment)),
+        }
+    }
+
+    fn status(&self) -> Vec<StatusField> {
+        let mut fields = vec![StatusField::Resource("logs".to_string(), self.logs())];
+        for (idx, plot) in self.plots.iter().enumerate() {
+            if let Some(left) = plot.time_left() {
+                fields.push(StatusField::Timer(format!("plot {}", idx), left));
+            }
+        }
+        fields
+    }
+}
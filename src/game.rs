@@ -1,19 +1,44 @@
 use crate::{
-    game_object::{GameObject, HandleError, Value},
-    lexer::Lexer,
-    parser::{Arg, Instruction, Parser},
-    term::{Term, WrapMode},
+    completion::Completion,
+    forest::Forest,
+    game_object::{
+        arg_count, int_arg, GameObject, HandleError, Member, MemberKind, Param, Segment,
+        StatusField, Value,
+    },
+    highlight::{highlight, prompt_lines},
+    history::{History, HistorySearch},
+    interpreter::Interpreter,
+    layout::{split, Direction, Panel, Rect, Size, TextView},
+    lexer::{Lexer, Span, Spanned, Token},
+    line_editor::LineEditor,
+    log::{Entry, EntryKind, Log},
+    parser::Parser,
+    status::StatusBar,
+    term::{skip_cells, Style, StyledSpan, Term, WrapMode},
 };
 use crossterm::{
-    event::{Event, KeyCode, KeyEvent, MouseEvent, MouseEventKind},
-    terminal::size,
+    event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind},
+    style::Color,
 };
 use serde::{Deserialize, Serialize};
-use std::{cmp, fs, io};
+use std::{cmp, env, fs, io, time::Duration};
+use unicode_width::UnicodeWidthStr;
+
+const HISTORY_FILE: &str = "game.history";
+/// Maximum number of commands kept in the history file, unless `HISTORY_SIZE_VAR` sets another.
+const HISTORY_SIZE: usize = 500;
+const HISTORY_SIZE_VAR: &str = "GAME_HISTORY_SIZE";
+/// Smaller terminals get a message asking to enlarge them instead of the game.
+const MIN_SCREEN_WIDTH: u16 = 30;
+const MIN_SCREEN_HEIGHT: u16 = 8;
+/// Narrower terminals only show the console, without the sidebar.
+const SIDEBAR_MIN_SCREEN_WIDTH: u16 = 60;
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Root {
     coins: i64,
+    #[serde(default)]
+    forest: Forest,
 }
 
 impl Root {
@@ -34,24 +59,57 @@ impl Root {
         };
         root
     }
+
+    fn tick(&mut self) {
+        self.forest.tick();
+    }
 }
 
 impl GameObject for Root {
-    fn handle(&mut self, instruction: Instruction) -> Result<Value, HandleError> {
-        match instruction {
-            Instruction::Access(key, _) if key == "coins".to_string() => {
-                Ok(Value::IntValue(self.coins))
-            }
-            Instruction::FunctionCall(name, args) if name == "add".to_string() => {
-                let Some(Arg::Int(amount)) = args.get(0) else {
-                    return Err(HandleError::WrongArgType("Int".to_string(), 0));
-                };
-                self.coins += amount;
+    fn class_name(&self) -> String {
+        "Root".to_string()
+    }
+
+    fn members(&self) -> Vec<Member> {
+        vec![
+            Member::property("coins", "Coins available to spend"),
+            Member::child("forest", None, "Plots of land to grow trees on"),
+            Member::method(
+                "add",
+                vec![Param::new("amount", "Int")],
+                "Adds coins, returning the new total",
+            ),
+        ]
+    }
+
+    fn handle(&mut self, segment: Segment) -> Result<Value, HandleError> {
+        match segment {
+            Segment::Access(key) if key == "coins" => Ok(Value::IntValue(self.coins)),
+            Segment::FunctionCall(name, args) if name == "add" => {
+                arg_count(&args, 1)?;
+                self.coins = self.coins.checked_add(int_arg(&args, 0)?).ok_or(
+                    HandleError::InvalidOperation("Integer overflow".to_string()),
+                )?;
                 Ok(Value::IntValue(self.coins))
             }
-            _ => self.return_err("Root".to_string(), instruction),
+            _ => self.return_err(segment),
         }
     }
+
+    fn child(&mut self, segment: Segment) -> Result<&mut dyn GameObject, HandleError> {
+        match segment {
+            Segment::Access(key) if key == "forest" => Ok(&mut self.forest),
+            _ => Err(self.not_found(segment)),
+        }
+    }
+
+    fn status(&self) -> Vec<StatusField> {
+        [
+            vec![StatusField::Resource("coins".to_string(), self.coins)],
+            self.forest.status(),
+        ]
+        .concat()
+    }
 }
 
 impl Drop for Root {
@@ -69,182 +127,527 @@ impl Drop for Root {
     }
 }
 
+/// Size of the history, read from the environment so it can change without rebuilding.
+fn history_size() -> usize {
+    env::var(HISTORY_SIZE_VAR)
+        .ok()
+        .and_then(|size| size.trim().parse().ok())
+        .unwrap_or(HISTORY_SIZE)
+}
+
+/// Log entries shown in the console panel, notices going to the event feed instead.
+const CONSOLE_ENTRIES: &[EntryKind] = &[EntryKind::Echo, EntryKind::Result, EntryKind::Error];
+
+/// Pushes a line for each property of `object` and of its children that need no arguments.
+fn properties(object: &mut dyn GameObject, prefix: &str, lines: &mut Vec<Vec<StyledSpan>>) {
+    for member in object.members() {
+        match member.kind {
+            MemberKind::Property => {
+                let Ok(value) = object.handle(Segment::Access(member.name.clone())) else {
+                    continue;
+                };
+                lines.push(vec![
+                    StyledSpan::plain(format!("{}{}: ", prefix, member.name)),
+                    StyledSpan::new(value.to_string(), Style::default().bold()),
+                ]);
+            }
+            MemberKind::Child(None) => {
+                if let Ok(child) = object.child(Segment::Access(member.name.clone())) {
+                    properties(child, &format!("{}{}.", prefix, member.name), lines);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// What commands are run on, the game's root with the console's log next to it.
+struct Console<'a> {
+    root: &'a mut Root,
+    log: &'a mut Log,
+}
+
+impl GameObject for Console<'_> {
+    fn class_name(&self) -> String {
+        self.root.class_name()
+    }
+
+    fn members(&self) -> Vec<Member> {
+        [
+            self.root.members(),
+            vec![Member::child("log", None, "Output of the console")],
+        ]
+        .concat()
+    }
+
+    fn handle(&mut self, segment: Segment) -> Result<Value, HandleError> {
+        self.root.handle(segment)
+    }
+
+    fn child(&mut self, segment: Segment) -> Result<&mut dyn GameObject, HandleError> {
+        match segment {
+            Segment::Access(key) if key == "log" => Ok(self.log),
+            _ => self.root.child(segment),
+        }
+    }
+}
+
+/// Whether `command` has more opening than closing parens, in which case Enter continues it on
+/// a new line instead of submitting it.
+fn unclosed(command: &str) -> bool {
+    let Ok(tokens) = Lexer::tokenize(command) else {
+        return false;
+    };
+    let depth: i64 = tokens
+        .iter()
+        .map(|token| match token.node {
+            Token::OpenParen => 1,
+            Token::CloseParen => -1,
+            _ => 0,
+        })
+        .sum();
+    depth > 0
+}
+
 #[derive(Clone)]
 pub struct Game {
     term: Term,
-    command_buffer: String,
-    line_buffer: Vec<String>,
-    cursor_offset: u16,
+    editor: LineEditor,
+    log: Log,
     scroll_offset: u16,
     event: Option<Event>,
     root: Root,
+    interpreter: Interpreter,
+    completion: Option<Completion>,
+    history: History,
+    search: Option<HistorySearch>,
+    /// Colors of the command in the prompt, updated as it is typed.
+    highlights: Vec<Spanned<Style>>,
+    /// Whether something changed since the last frame was drawn.
+    redraw: bool,
+    /// Time of the last clear screen, the console only showing entries pushed since.
+    cleared: Duration,
+    /// Area left inside the console panel in the last frame.
+    console_area: Rect,
+    status: StatusBar,
 }
 
 impl Game {
     pub fn new() -> io::Result<Self> {
+        let root = Root::new();
+        let mut status = StatusBar::default();
+        status.tick(root.status());
         Ok(Self {
             term: Term::new()?,
-            command_buffer: String::new(),
-            line_buffer: vec![],
-            cursor_offset: 0,
+            editor: LineEditor::new(),
+            log: Log::new(),
             scroll_offset: 0,
             event: None,
-            root: Root::new(),
+            root,
+            interpreter: Interpreter::new(),
+            completion: None,
+            history: History::load(HISTORY_FILE, history_size()),
+            search: None,
+            highlights: vec![],
+            redraw: true,
+            cleared: Duration::ZERO,
+            console_area: Rect::default(),
+            status,
         })
     }
 
-    fn scroll_down(&mut self) {
-        if self.scroll_offset != 0 {
-            self.scroll_offset -= 1;
-        }
+    fn scroll_down(&mut self, rows: u16) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(rows);
     }
 
-    fn scroll_up(&mut self) {
-        self.scroll_offset += 1;
-        let height = size().expect("Failed to get terminal size").1 - 2;
-        if self.line_buffer.len() <= height as usize {
-            self.scroll_offset = 0;
-        } else {
-            self.scroll_offset =
-                cmp::min(self.scroll_offset, self.line_buffer.len() as u16 - height);
-        }
+    /// Scrolls towards older entries, the scroll being clamped to the console's rows when drawn.
+    fn scroll_up(&mut self, rows: u16) {
+        self.scroll_offset = self.scroll_offset.saturating_add(rows);
     }
 
-    fn cursor_left(&mut self) {
-        if self.cursor_offset >= self.command_buffer.len() as u16 {
-            return;
-        }
-        self.cursor_offset += 1;
+    /// Rows scrolled by a page, keeping one row of the previous page in view.
+    fn page(&self) -> u16 {
+        cmp::max(self.console_area.height.saturating_sub(1), 1)
     }
 
-    fn cursor_right(&mut self) {
-        if self.cursor_offset <= 0 {
-            return;
+    /// Hides the console's entries, leaving the prompt as it is. They stay in the log, to be
+    /// searched or exported.
+    fn clear_screen(&mut self) {
+        self.cleared = self.log.elapsed();
+        self.scroll_offset = 0;
+    }
+
+    fn history_previous(&mut self) {
+        if let Some(command) = self.history.previous(self.editor.text()) {
+            self.editor.set(command);
         }
-        self.cursor_offset -= 1;
     }
 
-    fn char_at_cursor(&mut self, char: char) {
-        if !char.is_ascii() {
-            return;
+    fn history_next(&mut self) {
+        if let Some(command) = self.history.next() {
+            self.editor.set(command);
         }
-        self.command_buffer.insert(
-            self.command_buffer.len() - self.cursor_offset as usize,
-            char,
-        );
     }
 
-    fn remove_char_before(&mut self) {
-        if self.command_buffer.len() - self.cursor_offset as usize == 0 {
+    fn search_history(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let Some(search) = &mut self.search else {
             return;
+        };
+        match code {
+            KeyCode::Char('r') if modifiers.contains(KeyModifiers::CONTROL) => {
+                search.older(&self.history);
+            }
+            KeyCode::Char(char) => {
+                search.query.push(char);
+                search.refresh(&self.history);
+            }
+            KeyCode::Backspace => {
+                search.query.pop();
+                search.refresh(&self.history);
+            }
+            KeyCode::Enter => {
+                if let Some(command) = search.found.and_then(|idx| self.history.get(idx)) {
+                    self.editor.set(command);
+                }
+                self.search = None;
+            }
+            KeyCode::Esc => self.search = None,
+            _ => (),
         }
-        self.command_buffer
-            .remove(self.command_buffer.len() - self.cursor_offset as usize - 1);
     }
 
-    fn remove_char_at(&mut self) {
-        if self.cursor_offset == 0 {
-            return;
+    fn complete(&mut self) {
+        match &mut self.completion {
+            Some(completion) => completion.next(),
+            None => {
+                self.completion = Completion::new(
+                    self.editor.text(),
+                    self.editor.cursor(),
+                    &mut Console {
+                        root: &mut self.root,
+                        log: &mut self.log,
+                    },
+                    &self.interpreter,
+                );
+            }
         }
-        self.command_buffer
-            .remove(self.command_buffer.len() - self.cursor_offset as usize);
-        self.cursor_offset -= 1;
+        if let Some(completion) = &mut self.completion {
+            completion.apply(&mut self.editor);
+        }
+    }
+
+    /// Underlines `span` in the command echoed last, then logs the error message.
+    fn push_error(&mut self, span: Span, message: String) {
+        self.log.set_error(span);
+        self.log.push(Entry::Error(message));
     }
 
     fn submit_command(&mut self) {
-        self.cursor_offset = 0;
         self.scroll_offset = 0;
-        let command = self.command_buffer.clone();
-        self.line_buffer.push(format!("~ {}", command));
-        self.command_buffer.clear();
+        let command = self.editor.take();
+        self.log.push(Entry::Echo {
+            command: command.clone(),
+            // The highlights are still the ones of the submitted command
+            highlights: self.highlights.clone(),
+            error: None,
+        });
+        self.history.push(&command);
 
         let tokens = match Lexer::tokenize(&command) {
             Ok(ok) => ok,
             Err(err) => {
-                self.line_buffer.push(format!("{}", err));
+                self.push_error(err.span, format!("{}", err.node));
                 return;
             }
         };
 
-        let instruction = match Parser::parse(tokens) {
+        let statements = match Parser::parse(tokens) {
             Ok(ok) => ok,
             Err(err) => {
-                self.line_buffer.push(format!("{}", err));
+                self.push_error(err.span, format!("{}", err.node));
                 return;
             }
         };
 
-        let result = match self.root.handle(instruction) {
-            Ok(ok) => ok,
-            Err(err) => {
-                self.line_buffer.push(format!("{}", err));
-                return;
-            }
-        };
+        let count = statements.len();
+        for (idx, statement) in statements.into_iter().enumerate() {
+            let mut console = Console {
+                root: &mut self.root,
+                log: &mut self.log,
+            };
+            let result = match self.interpreter.execute(&mut console, statement) {
+                Ok(ok) => ok,
+                Err(err) if count > 1 => {
+                    self.push_error(
+                        err.span,
+                        format!("Statement {}/{} failed: {}", idx + 1, count, err.node),
+                    );
+                    return;
+                }
+                Err(err) => {
+                    self.push_error(err.span, format!("{}", err.node));
+                    return;
+                }
+            };
 
-        self.line_buffer.push(format!("{}", result));
+            self.log.push(Entry::Result(result));
+        }
     }
 
     pub fn update(&mut self) -> io::Result<bool> {
         self.event = self.term.poll_event()?;
+        if self.event.is_some() {
+            self.redraw = true;
+        }
+
+        if let Some(Event::Key(KeyEvent { code, .. })) = self.event {
+            if code != KeyCode::Tab {
+                self.completion = None;
+            }
+        }
 
         match self.event {
-            Some(Event::Key(KeyEvent { code, .. })) => match code {
+            Some(Event::Key(KeyEvent {
+                code, modifiers, ..
+            })) if self.search.is_some() => self.search_history(code, modifiers),
+            Some(Event::Key(KeyEvent {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            })) => self.search = Some(HistorySearch::default()),
+            Some(Event::Key(KeyEvent {
+                code: KeyCode::Char('l'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            })) => self.clear_screen(),
+            Some(Event::Key(key)) => match key.code {
                 KeyCode::Esc => return Ok(true),
-                KeyCode::Left => self.cursor_left(),
-                KeyCode::Right => self.cursor_right(),
-                KeyCode::Char(char) => self.char_at_cursor(char.clone()),
-                KeyCode::Backspace => self.remove_char_before(),
-                KeyCode::Delete => self.remove_char_at(),
+                KeyCode::PageUp => self.scroll_up(self.page()),
+                KeyCode::PageDown => self.scroll_down(self.page()),
+                KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) => self.scroll_up(1),
+                KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) => self.scroll_down(1),
+                KeyCode::Home if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.scroll_offset = u16::MAX
+                }
+                KeyCode::End if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.scroll_offset = 0
+                }
+                KeyCode::Up if !self.editor.line_up() => self.history_previous(),
+                KeyCode::Down if !self.editor.line_down() => self.history_next(),
+                KeyCode::Up | KeyCode::Down => (),
+                KeyCode::Enter
+                    if key
+                        .modifiers
+                        .intersects(KeyModifiers::SHIFT | KeyModifiers::ALT)
+                        || unclosed(self.editor.text()) =>
+                {
+                    self.editor.newline()
+                }
                 KeyCode::Enter => self.submit_command(),
-                _ => (),
+                KeyCode::Tab => self.complete(),
+                _ => {
+                    self.editor.handle_key(key);
+                }
             },
+            Some(Event::Resize(width, height)) => self.term.resize(width, height),
             Some(Event::Mouse(MouseEvent { kind, .. })) => match kind {
-                MouseEventKind::ScrollUp => self.scroll_up(),
-                MouseEventKind::ScrollDown => self.scroll_down(),
+                MouseEventKind::ScrollUp => self.scroll_up(1),
+                MouseEventKind::ScrollDown => self.scroll_down(1),
                 _ => (),
             },
             _ => (),
         }
 
+        if let Some(Event::Key(_)) = self.event {
+            let mut console = Console {
+                root: &mut self.root,
+                log: &mut self.log,
+            };
+            self.highlights = highlight(self.editor.text(), &mut console, &self.interpreter);
+            // Commands may have changed what the status bar shows
+            self.status.refresh(self.root.status());
+        }
+
         Ok(false)
     }
 
     pub fn fixed_update(&mut self) -> io::Result<()> {
-        self.line_buffer.push("X seconds".to_string());
+        self.root.tick();
+        self.status.tick(self.root.status());
+        self.redraw = true;
+        self.log.push(Entry::Notice("X seconds".to_string()));
+        Ok(())
+    }
+
+    /// Lines of the resource sidebar, the properties of the root and of its children.
+    fn resources(&mut self) -> Vec<Vec<StyledSpan>> {
+        let mut lines = vec![];
+        properties(&mut self.root, "", &mut lines);
+        lines
+    }
+
+    /// Shown instead of the game when the terminal can't fit it.
+    fn draw_too_small(&mut self, width: u16, height: u16) -> io::Result<()> {
+        let message = format!(
+            "Terminal too small: {}x{}, needs at least {}x{}",
+            width, height, MIN_SCREEN_WIDTH, MIN_SCREEN_HEIGHT
+        );
+        if width > 0 && height > 0 {
+            self.term
+                .print_spans(
+                    &[StyledSpan::new(message, Style::fg(Color::Yellow))],
+                    0,
+                    0,
+                    width,
+                    WrapMode::Normal,
+                )?
+                .move_cursor(0, 0)?;
+        }
+        self.term.flush()?;
         Ok(())
     }
 
     pub fn draw(&mut self) -> io::Result<()> {
+        if !self.redraw {
+            return Ok(());
+        }
+        self.redraw = false;
         self.term.clear()?;
 
-        for (idx, line) in self
-            .line_buffer
-            .iter()
-            .rev()
-            .skip(self.scroll_offset as usize)
-            .take(size()?.1 as usize - 2)
-            .rev()
-            .enumerate()
-        {
-            self.term
-                .print_wrap(&line, 0, idx as u16, size()?.0, WrapMode::Normal)?;
+        let (width, height) = self.term.size();
+        if width < MIN_SCREEN_WIDTH || height < MIN_SCREEN_HEIGHT {
+            return self.draw_too_small(width, height);
+        }
+        let prompt_height = if self.search.is_some() {
+            1
+        } else {
+            cmp::min(self.editor.line_count() as u16, height.saturating_sub(3))
+        };
+        let rows = split(
+            Rect::new(0, 0, width, height),
+            Direction::Vertical,
+            &[
+                Size::Fixed(1),
+                Size::Min(3),
+                Size::Fixed(self.completion.is_some() as u16),
+                Size::Fixed(prompt_height),
+            ],
+        );
+        let (status_area, top, completion_area, prompt_area) = (rows[0], rows[1], rows[2], rows[3]);
+        self.term.print_spans(
+            &self.status.spans(),
+            status_area.x,
+            status_area.y,
+            status_area.width,
+            WrapMode::Cut,
+        )?;
+        let sidebar_width = if width >= SIDEBAR_MIN_SCREEN_WIDTH {
+            Size::Percent(30)
+        } else {
+            Size::Fixed(0)
+        };
+        let columns = split(top, Direction::Horizontal, &[Size::Min(20), sidebar_width]);
+        let sidebar = split(
+            columns[1],
+            Direction::Vertical,
+            &[Size::Min(3), Size::Percent(50)],
+        );
+
+        let console = Panel::new("Console").draw(&mut self.term, columns[0])?;
+        self.console_area = console;
+        self.scroll_offset = TextView::new(
+            self.log.lines(CONSOLE_ENTRIES, self.cleared),
+            self.scroll_offset as usize,
+        )
+        .wrap(WrapMode::Word)
+        .draw(&mut self.term, console)? as u16;
+        if self.scroll_offset > 0 && !console.is_empty() {
+            // Shown over the bottom border of the console
+            let below = format!(" -- {} lines below -- ", self.scroll_offset);
+            let below_width = cmp::min(below.len() as u16, console.width);
+            self.term.print_spans(
+                &[StyledSpan::new(below, Style::fg(Color::Yellow))],
+                console.x + console.width - below_width,
+                console.y + console.height,
+                below_width,
+                WrapMode::Cut,
+            )?;
         }
 
-        /*
-        if let Some(event) = &self.event {
-            self.term.print(&format!("{:?}", event), 1, 1)?;
-        }else {
-            self.term.print("...", 1, 1)?;
+        let resources = Panel::new("Resources").draw(&mut self.term, sidebar[0])?;
+        TextView::new(self.resources(), 0).draw(&mut self.term, resources)?;
+
+        let events = Panel::new("Events").draw(&mut self.term, sidebar[1])?;
+        TextView::new(self.log.lines(&[EntryKind::Notice], Duration::ZERO), 0)
+            .wrap(WrapMode::Word)
+            .draw(&mut self.term, events)?;
+
+        if let Some(completion) = &self.completion {
+            let candidates: Vec<StyledSpan> = completion
+                .candidates
+                .iter()
+                .enumerate()
+                .map(|(idx, candidate)| {
+                    let label = format!(" {} ", candidate.label);
+                    if idx == completion.index {
+                        StyledSpan::new(label, Style::default().reverse())
+                    } else {
+                        StyledSpan::plain(label)
+                    }
+                })
+                .collect();
+            self.term.print_spans(
+                &candidates,
+                completion_area.x,
+                completion_area.y,
+                completion_area.width,
+                WrapMode::Cut,
+            )?;
         }
-        */
 
+        if let Some(search) = &self.search {
+            let found = search.found.and_then(|idx| self.history.get(idx));
+            let prompt = format!(
+                "({}reverse-i-search)'{}': ",
+                if found.is_none() && !search.query.is_empty() {
+                    "failed "
+                } else {
+                    ""
+                },
+                search.query
+            );
+            self.term
+                .print(
+                    &format!("{}{}", prompt, found.unwrap_or_default().replace('\n', " ")),
+                    0,
+                    prompt_area.y,
+                )?
+                .move_cursor(prompt.width() as u16 - 3, prompt_area.y)?
+                .flush()?;
+            return Ok(());
+        }
+        // Lines and columns that do not fit are scrolled past so the cursor stays visible
+        let (row, column) = self.editor.cursor_position();
+        let first = (row + 1).saturating_sub(prompt_area.height as usize);
+        let left = (2 + column + 1).saturating_sub(width as usize);
+        for (idx, line) in prompt_lines(self.editor.text(), &self.highlights)
+            .iter()
+            .skip(first)
+            .take(prompt_area.height as usize)
+            .enumerate()
+        {
+            self.term.print_spans(
+                &skip_cells(line, left),
+                0,
+                prompt_area.y + idx as u16,
+                width,
+                WrapMode::Cut,
+            )?;
+        }
         self.term
-            .line('-', 0, size()?.1 - 2, size()?.0, false)?
-            .print(&format!("~ {}", self.command_buffer), 0, size()?.1 - 1)?
             .move_cursor(
-                2 + self.command_buffer.len() as u16 - self.cursor_offset,
-                size()?.1 - 1,
+                (2 + column - left) as u16,
+                prompt_area.y + (row - first) as u16,
             )?
             .flush()?;
         Ok(())
@@ -1,29 +1,114 @@
-use std::fmt::Display;
+use std::{cmp, fmt::Display};
 
-use crate::parser::Instruction;
+use crate::parser::Literal;
 
 #[derive(Debug, Clone)]
 pub enum HandleError {
-    WrongArgType(String, u16),
+    WrongArgType(String, u16, Option<String>),
+    /// Number of arguments expected by a call, and number it got.
+    WrongArgCount(usize, usize),
     NotFound(String),
+    /// A property or function missing from an object, along with close names that do exist.
+    UnknownMember {
+        name: String,
+        class_name: String,
+        function: bool,
+        suggestions: Vec<String>,
+    },
+    /// A member that exists but was used as another kind, e.g. a property followed by a `.`.
+    WrongMemberKind(String),
+    Failed(String),
+    InvalidOperation(String),
 }
 
 impl Display for HandleError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::WrongArgType(expected, at) => {
-                write!(f, "Expected argument of type '{}' at {}", expected, at)
-            }
+            Self::WrongArgType(expected, at, got) => write!(
+                f,
+                "Expected argument of type '{}' at {} but got {}",
+                expected,
+                at,
+                got.clone()
+                    .map_or("none".to_string(), |got| format!("'{}'", got))
+            ),
+            Self::WrongArgCount(expected, got) => write!(
+                f,
+                "Expected {} argument{} but got {}",
+                expected,
+                if *expected == 1 { "" } else { "s" },
+                got
+            ),
             Self::NotFound(str) => write!(f, "{}", str),
+            Self::UnknownMember {
+                name,
+                class_name,
+                function,
+                suggestions,
+            } => {
+                if *function {
+                    write!(
+                        f,
+                        "Did not find any functions named '{}' on {}",
+                        name, class_name
+                    )?;
+                } else {
+                    write!(f, "Did not find property '{}' on {}", name, class_name)?;
+                }
+                if let Some((last, rest)) = suggestions.split_last() {
+                    let rest = rest
+                        .iter()
+                        .map(|suggestion| format!("'{}'", suggestion))
+                        .collect::<Vec<String>>();
+                    if rest.is_empty() {
+                        write!(f, " \u{2014} did you mean '{}'?", last)?;
+                    } else {
+                        write!(
+                            f,
+                            " \u{2014} did you mean {} or '{}'?",
+                            rest.join(", "),
+                            last
+                        )?;
+                    }
+                }
+                Ok(())
+            }
+            Self::WrongMemberKind(str) => write!(f, "{}", str),
+            Self::Failed(str) => write!(f, "{}", str),
+            Self::InvalidOperation(str) => write!(f, "{}", str),
         }
     }
 }
 
 #[derive(Debug, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum Value {
     Null,
     IntValue(i64),
+    FloatValue(f64),
     BoolValue(bool),
+    StringValue(String),
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Null => "Null",
+            Self::IntValue(_) => "Int",
+            Self::FloatValue(_) => "Float",
+            Self::BoolValue(_) => "Bool",
+            Self::StringValue(_) => "String",
+        }
+    }
+
+    /// Numeric view of the value, ints are widened to floats.
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::IntValue(int) => Some(*int as f64),
+            Self::FloatValue(float) => Some(*float),
+            _ => None,
+        }
+    }
 }
 
 impl Display for Value {
@@ -31,27 +116,278 @@ impl Display for Value {
         match self {
             Self::Null => write!(f, "Null"),
             Self::IntValue(int) => write!(f, "{}", int),
+            Self::FloatValue(float) => write!(f, "{:?}", float),
             Self::BoolValue(bool) => write!(f, "{}", bool),
+            Self::StringValue(string) => write!(f, "{}", string),
         }
     }
 }
 
-pub trait GameObject {
-    fn handle(&mut self, instruction: Instruction) -> Result<Value, HandleError>;
-    fn return_err(
-        &self,
-        class_name: String,
-        instruction: Instruction,
-    ) -> Result<Value, HandleError> {
-        match instruction {
-            Instruction::FunctionCall(name, _) => Err(HandleError::NotFound(format!(
-                "Did not find any functions named '{}' on {}",
-                name, class_name
-            ))),
-            Instruction::Access(key, _) => Err(HandleError::NotFound(format!(
-                "Did not find property '{}' on {}",
-                key, class_name
-            ))),
+impl From<Literal> for Value {
+    fn from(literal: Literal) -> Self {
+        match literal {
+            Literal::Null => Self::Null,
+            Literal::Int(int) => Self::IntValue(int),
+            Literal::Float(float) => Self::FloatValue(float),
+            Literal::Bool(bool) => Self::BoolValue(bool),
+            Literal::String(string) => Self::StringValue(string),
         }
     }
 }
+
+fn wrong_arg_type(expected: &str, at: u16, got: Option<&Value>) -> HandleError {
+    HandleError::WrongArgType(
+        expected.to_string(),
+        at,
+        got.map(|got| got.type_name().to_string()),
+    )
+}
+
+/// Checks that a call got exactly `count` arguments, their types being checked by the others.
+pub fn arg_count(args: &[Value], count: usize) -> Result<(), HandleError> {
+    if args.len() == count {
+        Ok(())
+    } else {
+        Err(HandleError::WrongArgCount(count, args.len()))
+    }
+}
+
+pub fn int_arg(args: &[Value], at: u16) -> Result<i64, HandleError> {
+    match args.get(at as usize) {
+        Some(Value::IntValue(int)) => Ok(*int),
+        got => Err(wrong_arg_type("Int", at, got)),
+    }
+}
+
+pub fn string_arg(args: &[Value], at: u16) -> Result<&str, HandleError> {
+    match args.get(at as usize) {
+        Some(Value::StringValue(string)) => Ok(string),
+        got => Err(wrong_arg_type("String", at, got)),
+    }
+}
+
+/// One step of a path like `forest.plot(2).harvest()`, with its arguments already evaluated.
+#[derive(Debug, Clone)]
+pub enum Segment {
+    Access(String),
+    FunctionCall(String, Vec<Value>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: &'static str,
+    pub type_name: &'static str,
+}
+
+impl Param {
+    pub fn new(name: &'static str, type_name: &'static str) -> Self {
+        Self { name, type_name }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum MemberKind {
+    Property,
+    Method(Vec<Param>),
+    /// A child object, reached either by accessing it or by calling it with the given params.
+    Child(Option<Vec<Param>>),
+}
+
+/// Description of something that can be reached on a `GameObject`, used for introspection.
+#[derive(Debug, Clone)]
+pub struct Member {
+    pub name: String,
+    pub kind: MemberKind,
+    pub description: &'static str,
+}
+
+impl Member {
+    pub fn property(name: &str, description: &'static str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: MemberKind::Property,
+            description,
+        }
+    }
+
+    pub fn method(name: &str, params: Vec<Param>, description: &'static str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: MemberKind::Method(params),
+            description,
+        }
+    }
+
+    pub fn child(name: &str, params: Option<Vec<Param>>, description: &'static str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: MemberKind::Child(params),
+            description,
+        }
+    }
+
+    /// Whether the member is used with parentheses.
+    pub fn is_callable(&self) -> bool {
+        matches!(
+            self.kind,
+            MemberKind::Method(_) | MemberKind::Child(Some(_))
+        )
+    }
+
+    /// How the member is written, e.g. `coins`, `add(amount: Int)` or `plot(idx: Int).`.
+    pub fn signature(&self) -> String {
+        let params = |params: &[Param]| {
+            params
+                .iter()
+                .map(|param| format!("{}: {}", param.name, param.type_name))
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+        match &self.kind {
+            MemberKind::Property => self.name.clone(),
+            MemberKind::Method(method_params) => {
+                format!("{}({})", self.name, params(method_params))
+            }
+            MemberKind::Child(None) => format!("{}.", self.name),
+            MemberKind::Child(Some(child_params)) => {
+                format!("{}({}).", self.name, params(child_params))
+            }
+        }
+    }
+}
+
+/// Number of single char insertions, deletions or substitutions to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Names of `members` close enough to `name` to likely be what was meant, closest first. None
+/// when `name` is a member, as it was then misused rather than mistyped.
+pub fn suggestions(name: &str, members: &[Member]) -> Vec<String> {
+    if members.iter().any(|member| member.name == name) {
+        return vec![];
+    }
+    let max_distance = cmp::max(2, name.chars().count() / 3);
+    let mut close: Vec<(usize, &str)> = members
+        .iter()
+        .map(|member| member.name.as_str())
+        .filter(|member| *member != name)
+        .map(|member| (edit_distance(name, member), member))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    close.sort_by_key(|(distance, _)| *distance);
+    close.dedup_by_key(|(_, member)| *member);
+    close
+        .into_iter()
+        .take(3)
+        .map(|(_, member)| member.to_string())
+        .collect()
+}
+
+/// Error for `member` being used as a method if `function`, and as an object holding other
+/// members if `object`. `None` when the member can be used that way.
+pub fn wrong_kind(member: &Member, function: bool, object: bool) -> Option<HandleError> {
+    let name = &member.name;
+    let signature = member.signature();
+    let message = match (&member.kind, object) {
+        (MemberKind::Child(_), false) => format!(
+            "'{}' is an object, access one of its members or use inspect({})",
+            name,
+            signature.trim_end_matches('.')
+        ),
+        (MemberKind::Child(None), true) if function => {
+            format!("'{}' is an object, access it without parentheses", name)
+        }
+        (MemberKind::Child(Some(_)), true) if !function => format!(
+            "'{}' is an object reached with {}",
+            name,
+            signature.trim_end_matches('.')
+        ),
+        (MemberKind::Child(_), true) => return None,
+        (MemberKind::Property, true) => format!("'{}' is a property, not an object", name),
+        (MemberKind::Method(_), true) => format!("'{}' is a method, not an object", name),
+        (MemberKind::Property, false) if function => {
+            format!("'{}' is a property, not a method", name)
+        }
+        (MemberKind::Method(_), false) if !function => {
+            format!("'{}' is a method, call it with {}", name, signature)
+        }
+        _ => return None,
+    };
+    Some(HandleError::WrongMemberKind(message))
+}
+
+/// Error for a segment `object` has no use for, `child` telling whether an object was expected.
+fn unknown_member<T: GameObject + ?Sized>(
+    object: &T,
+    segment: Segment,
+    child: bool,
+) -> HandleError {
+    let (name, function) = match segment {
+        Segment::FunctionCall(name, _) => (name, true),
+        Segment::Access(key) => (key, false),
+    };
+    let members = object.members();
+    if let Some(error) = members
+        .iter()
+        .find(|member| member.name == name)
+        .and_then(|member| wrong_kind(member, function, child))
+    {
+        return error;
+    }
+    HandleError::UnknownMember {
+        suggestions: suggestions(&name, &members),
+        name,
+        class_name: object.class_name(),
+        function,
+    }
+}
+
+/// Something shown in the status bar, labelled with its name.
+#[derive(Debug, Clone)]
+pub enum StatusField {
+    /// An amount, shown along with how fast it changes.
+    Resource(String, i64),
+    /// Seconds left until something is done.
+    Timer(String, i64),
+}
+
+pub trait GameObject {
+    fn class_name(&self) -> String;
+
+    /// Every property, method and child reachable on this object.
+    fn members(&self) -> Vec<Member>;
+
+    /// Handles the last segment of a path, either reading a property or calling a method.
+    fn handle(&mut self, segment: Segment) -> Result<Value, HandleError>;
+
+    /// Returns the child object a segment in the middle of a path points to.
+    fn child(&mut self, segment: Segment) -> Result<&mut dyn GameObject, HandleError> {
+        Err(self.not_found(segment))
+    }
+
+    /// Error for a segment that isn't a child, from `child`.
+    fn not_found(&self, segment: Segment) -> HandleError {
+        unknown_member(self, segment, true)
+    }
+
+    /// Error for a segment that isn't a property or method, from `handle`.
+    fn return_err(&self, segment: Segment) -> Result<Value, HandleError> {
+        Err(unknown_member(self, segment, false))
+    }
+
+    /// Fields this object wants shown in the status bar, read again every tick.
+    fn status(&self) -> Vec<StatusField> {
+        vec![]
+    }
+}
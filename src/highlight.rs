@@ -0,0 +1,175 @@
+use crate::{
+    completion::Completion,
+    game_object::GameObject,
+    interpreter::Interpreter,
+    lexer::{Lexer, Span, Spanned, Token},
+    term::{Style, StyledSpan},
+};
+use crossterm::style::Color;
+use std::ops::Range;
+
+const KEYWORD: Color = Color::Magenta;
+const NUMBER: Color = Color::Yellow;
+const STRING: Color = Color::Green;
+const CONSTANT: Color = Color::Cyan;
+const PUNCTUATION: Color = Color::DarkGrey;
+const MEMBER: Color = Color::Blue;
+const UNKNOWN: Color = Color::Red;
+
+/// Styles of the parts of `command`, sorted by position. Parts without a style are left plain.
+pub fn highlight(
+    command: &str,
+    root: &mut dyn GameObject,
+    interpreter: &Interpreter,
+) -> Vec<Spanned<Style>> {
+    // Everything before a lexer error is still highlighted, the rest is marked as erroneous
+    let (tokens, error) = match Lexer::tokenize(command) {
+        Ok(tokens) => (tokens, None),
+        Err(err) => (
+            Lexer::tokenize(&command[..err.span.start]).unwrap_or_default(),
+            Some(Span::new(err.span.start, command.len())),
+        ),
+    };
+
+    let mut globals: Vec<String> = [root.members(), interpreter.globals()]
+        .concat()
+        .into_iter()
+        .map(|member| member.name)
+        .collect();
+
+    let mut styles = vec![];
+    for (idx, token) in tokens.iter().enumerate() {
+        let previous = idx.checked_sub(1).map(|previous| &tokens[previous].node);
+        let style = match &token.node {
+            Token::Keyword(keyword)
+                if keyword == "let"
+                    && previous.is_none_or(|token| matches!(token, Token::Semicolon)) =>
+            {
+                Style::fg(KEYWORD).bold()
+            }
+            // Variables are known as soon as they are declared
+            Token::Keyword(name) if matches!(previous, Some(Token::Keyword(keyword)) if keyword == "let") =>
+            {
+                globals.push(name.clone());
+                Style::fg(MEMBER)
+            }
+            Token::Keyword(name) if matches!(previous, Some(Token::Dot)) => {
+                match member_names(command, tokens[idx - 1].span.start, root, interpreter) {
+                    Some(names) if names.contains(name) => Style::fg(MEMBER),
+                    Some(_) => Style::fg(UNKNOWN).underline(),
+                    // Members of objects that can't be resolved without running a method
+                    None => continue,
+                }
+            }
+            Token::Keyword(name) if globals.contains(name) => Style::fg(MEMBER),
+            Token::Keyword(_) => Style::fg(UNKNOWN).underline(),
+            Token::Int(_) | Token::Float(_) => Style::fg(NUMBER),
+            Token::String(_) => Style::fg(STRING),
+            Token::Bool(_) | Token::Null => Style::fg(CONSTANT),
+            _ => Style::fg(PUNCTUATION),
+        };
+        styles.push(Spanned::new(style, token.span));
+    }
+    if let Some(span) = error {
+        styles.push(Spanned::new(
+            Style::fg(Color::White).bg(Color::DarkRed),
+            span,
+        ));
+    }
+    styles
+}
+
+/// Names of the members of the object whose path ends right before the `.` at `dot`.
+fn member_names(
+    command: &str,
+    dot: usize,
+    root: &mut dyn GameObject,
+    interpreter: &Interpreter,
+) -> Option<Vec<String>> {
+    let instruction = Completion::object_path(&command[..dot])?;
+    let object = interpreter.object(root, instruction).ok()?;
+    Some(
+        object
+            .members()
+            .into_iter()
+            .map(|member| member.name)
+            .collect(),
+    )
+}
+
+/// Bytes `range` of `command` split into spans styled by `highlights`.
+pub fn styled(
+    command: &str,
+    highlights: &[Spanned<Style>],
+    range: Range<usize>,
+) -> Vec<StyledSpan> {
+    let mut spans = vec![];
+    let mut written = range.start;
+    for highlight in highlights {
+        let start = highlight.span.start.clamp(written, range.end);
+        let end = highlight.span.end.clamp(start, range.end);
+        if start == end {
+            continue;
+        }
+        if written < start {
+            spans.push(StyledSpan::plain(&command[written..start]));
+        }
+        spans.push(StyledSpan::new(&command[start..end], highlight.node));
+        written = end;
+    }
+    if written < range.end {
+        spans.push(StyledSpan::plain(&command[written..range.end]));
+    }
+    spans
+}
+
+/// Lines of `command` as shown in the prompt, continuation lines starting with `.` instead of `~`.
+pub fn prompt_lines(command: &str, highlights: &[Spanned<Style>]) -> Vec<Vec<StyledSpan>> {
+    let mut start = 0;
+    command
+        .split('\n')
+        .enumerate()
+        .map(|(idx, line)| {
+            let prompt = if idx == 0 { "~ " } else { ". " };
+            let mut spans = vec![StyledSpan::new(prompt, Style::fg(Color::DarkGrey))];
+            spans.extend(styled(command, highlights, start..start + line.len()));
+            start += line.len() + 1;
+            spans
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forest::Forest;
+
+    fn style_of(command: &str, part: &str) -> Option<Style> {
+        let start = command.find(part).unwrap();
+        highlight(command, &mut Forest::default(), &Interpreter::new())
+            .into_iter()
+            .find(|highlight| highlight.span.start == start)
+            .map(|highlight| highlight.node)
+    }
+
+    #[test]
+    fn members_are_resolved_across_multi_byte_whitespace() {
+        for command in [
+            "plot(0).logs",
+            "plot(0). logs",
+            "plot(0).\u{a0}logs",
+            "plot(0).\u{3000}logs",
+        ] {
+            assert_eq!(
+                style_of(command, "logs"),
+                Some(Style::fg(MEMBER)),
+                "{:?}",
+                command
+            );
+        }
+        assert_eq!(
+            style_of("plot(0).\u{a0}log", "log"),
+            Some(Style::fg(UNKNOWN).underline())
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use std::fs;
+
+/// Previously submitted commands, oldest first, saved to a file when dropped.
+#[derive(Clone)]
+pub struct History {
+    entries: Vec<String>,
+    path: String,
+    limit: usize,
+    /// Entry currently recalled in the prompt, `None` while typing a new command.
+    position: Option<usize>,
+    /// Command that was being typed before recalling entries, restored past the newest one.
+    draft: String,
+}
+
+impl History {
+    pub fn load(path: &str, limit: usize) -> Self {
+        // A missing file just means no command was submitted yet
+        let entries = fs::read_to_string(path)
+            .map(|file| file.lines().map(unescape).collect())
+            .unwrap_or_default();
+        let mut history = Self {
+            entries,
+            path: path.to_string(),
+            limit,
+            position: None,
+            draft: String::new(),
+        };
+        history.truncate();
+        history
+    }
+
+    fn truncate(&mut self) {
+        if self.entries.len() > self.limit {
+            self.entries.drain(..self.entries.len() - self.limit);
+        }
+    }
+
+    pub fn push(&mut self, command: &str) {
+        self.position = None;
+        self.draft.clear();
+        if command.trim().is_empty() || self.entries.last().is_some_and(|last| last == command) {
+            return;
+        }
+        self.entries.push(command.to_string());
+        self.truncate();
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&str> {
+        self.entries.get(idx).map(String::as_str)
+    }
+
+    /// Index of the newest entry older than `before` that contains `query`.
+    pub fn search(&self, query: &str, before: usize) -> Option<usize> {
+        self.entries[..before.min(self.entries.len())]
+            .iter()
+            .rposition(|entry| entry.contains(query))
+    }
+
+    /// Steps to the previous entry, `current` being the command in the prompt right now.
+    pub fn previous(&mut self, current: &str) -> Option<&str> {
+        let position = match self.position {
+            None => {
+                self.draft = current.to_string();
+                self.entries.len().checked_sub(1)?
+            }
+            Some(position) => position.checked_sub(1)?,
+        };
+        self.position = Some(position);
+        Some(&self.entries[position])
+    }
+
+    /// Steps to the next entry, giving back the draft once past the newest one.
+    pub fn next(&mut self) -> Option<&str> {
+        let position = self.position? + 1;
+        if position >= self.entries.len() {
+            self.position = None;
+            return Some(&self.draft);
+        }
+        self.position = Some(position);
+        Some(&self.entries[position])
+    }
+}
+
+/// Escapes newlines so that a multi-line command is saved on a single line of the file.
+fn escape(entry: &str) -> String {
+    entry.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(line: &str) -> String {
+    let mut entry = String::new();
+    let mut chars = line.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            entry.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => entry.push('\n'),
+            Some(escaped) => entry.push(escaped),
+            None => entry.push('\\'),
+        }
+    }
+    entry
+}
+
+/// State of a reverse incremental search through the history.
+#[derive(Clone, Default)]
+pub struct HistorySearch {
+    pub query: String,
+    /// Entry matching the query, `None` when nothing matches.
+    pub found: Option<usize>,
+}
+
+impl HistorySearch {
+    /// Looks for the newest match of the query, used after the query changed.
+    pub fn refresh(&mut self, history: &History) {
+        self.found = history.search(&self.query, usize::MAX);
+    }
+
+    /// Steps to the next older match, keeping the current one if there is none.
+    pub fn older(&mut self, history: &History) {
+        let before = self.found.unwrap_or(usize::MAX);
+        if let Some(found) = history.search(&self.query, before) {
+            self.found = Some(found);
+        }
+    }
+}
+
+impl Drop for History {
+    fn drop(&mut self) {
+        let entries: Vec<String> = self.entries.iter().map(|entry| escape(entry)).collect();
+        if let Err(err) = fs::write(&self.path, entries.join("\n")) {
+            println!("Failed to save command history to file: {}", err);
+        }
+    }
+}
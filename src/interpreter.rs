@@ -0,0 +1,552 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use crate::{
+    game_object::{
+        suggestions, wrong_kind, GameObject, HandleError, Member, MemberKind, Param, Segment, Value,
+    },
+    lexer::{Span, Spanned},
+    parser::{BinaryOp, Expression, Instruction, Statement, UnaryOp},
+};
+
+/// Evaluates statements against the root object, keeping the player's variables between commands.
+#[derive(Clone, Default)]
+pub struct Interpreter {
+    variables: HashMap<String, Value>,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn execute(
+        &mut self,
+        root: &mut dyn GameObject,
+        statement: Statement,
+    ) -> Result<Value, Spanned<HandleError>> {
+        match statement {
+            Statement::Let(name, expression) => {
+                let value = self.evaluate(root, expression)?;
+                self.variables.insert(name.node, value.clone());
+                Ok(value)
+            }
+            Statement::Expression(expression) => self.evaluate(root, expression),
+        }
+    }
+
+    fn evaluate(
+        &self,
+        root: &mut dyn GameObject,
+        expression: Spanned<Expression>,
+    ) -> Result<Value, Spanned<HandleError>> {
+        let span = expression.span;
+        match expression.node {
+            Expression::Literal(literal) => Ok(Value::from(literal)),
+            Expression::Instruction(Instruction::Access(key, None))
+                if self.variables.contains_key(&key.node) =>
+            {
+                Ok(self.variables[&key.node].clone())
+            }
+            Expression::Instruction(Instruction::FunctionCall(name, args, None))
+                if name.node == "help" =>
+            {
+                if let Some(extra) = args.first() {
+                    return Err(Spanned::new(
+                        HandleError::Failed(format!(
+                            "help() takes no arguments but got {}",
+                            args.len()
+                        )),
+                        extra.span,
+                    ));
+                }
+                Ok(help(root))
+            }
+            Expression::Instruction(Instruction::FunctionCall(name, mut args, None))
+                if name.node == "inspect" =>
+            {
+                if let Some(extra) = args.get(1) {
+                    return Err(Spanned::new(
+                        HandleError::Failed(format!(
+                            "inspect() takes at most 1 argument but got {}",
+                            args.len()
+                        )),
+                        extra.span,
+                    ));
+                }
+                // The argument is a path to an object, which is resolved instead of evaluated
+                let object = match args.pop() {
+                    None => root,
+                    Some(Spanned {
+                        node: Expression::Instruction(instruction),
+                        ..
+                    }) => self.object(root, instruction)?,
+                    Some(arg) => {
+                        let span = arg.span;
+                        let got = self.evaluate(root, arg)?.type_name().to_string();
+                        return Err(Spanned::new(
+                            HandleError::WrongArgType("Object".to_string(), 0, Some(got)),
+                            span,
+                        ));
+                    }
+                };
+                Ok(inspect(object))
+            }
+            Expression::Instruction(instruction) => {
+                let mut path = self.path(root, instruction)?;
+                if let [Step {
+                    segment: Segment::FunctionCall(name, args),
+                    location,
+                }] = path.as_slice()
+                {
+                    if let Some(result) = builtin(name, args) {
+                        return result.map_err(|err| location.locate(err));
+                    }
+                }
+
+                let top_level = path.len() == 1;
+                let last = path
+                    .pop()
+                    .expect("Parsed instructions have at least one segment");
+                match walk(root, path)?.handle(last.segment) {
+                    // Variables and builtins live next to the root's members, so they are
+                    // worth suggesting too
+                    Err(HandleError::UnknownMember {
+                        name,
+                        class_name,
+                        function,
+                        ..
+                    }) if top_level => {
+                        let members = [root.members(), self.globals()].concat();
+                        let error = members
+                            .iter()
+                            .find(|member| member.name == name)
+                            .and_then(|member| wrong_kind(member, function, false))
+                            .unwrap_or_else(|| HandleError::UnknownMember {
+                                suggestions: suggestions(&name, &members),
+                                name,
+                                class_name,
+                                function,
+                            });
+                        Err(last.location.locate(error))
+                    }
+                    result => result.map_err(|err| last.location.locate(err)),
+                }
+            }
+            Expression::Unary(op, operand) => {
+                unary(op, self.evaluate(root, *operand)?).map_err(|err| Spanned::new(err, span))
+            }
+            Expression::Binary(left, op, right) => {
+                let left = self.evaluate(root, *left)?;
+                // `&&` and `||` short-circuit, the right side is only evaluated when needed
+                match (op, &left) {
+                    (BinaryOp::And, Value::BoolValue(false))
+                    | (BinaryOp::Or, Value::BoolValue(true)) => Ok(left),
+                    _ => binary(op, left, self.evaluate(root, *right)?)
+                        .map_err(|err| Spanned::new(err, span)),
+                }
+            }
+        }
+    }
+
+    /// Resolves an instruction that points to an object rather than a value, like `forest.plot(1)`.
+    pub fn object<'a>(
+        &self,
+        root: &'a mut dyn GameObject,
+        instruction: Instruction,
+    ) -> Result<&'a mut dyn GameObject, Spanned<HandleError>> {
+        let path = self.path(root, instruction)?;
+        walk(root, path)
+    }
+
+    /// Variables and builtins, which can start an expression besides the root's members.
+    pub fn globals(&self) -> Vec<Member> {
+        self.variables
+            .keys()
+            .map(|name| Member::property(name, "Variable set with let"))
+            .chain(builtins())
+            .collect()
+    }
+
+    /// Flattens an instruction into a path, evaluating every call's arguments from left to right.
+    fn path(
+        &self,
+        root: &mut dyn GameObject,
+        instruction: Instruction,
+    ) -> Result<Vec<Step>, Spanned<HandleError>> {
+        let mut path = vec![];
+        let mut next = Some(instruction);
+        while let Some(instruction) = next.take() {
+            let rest = match instruction {
+                Instruction::Access(key, rest) => {
+                    path.push(Step {
+                        segment: Segment::Access(key.node),
+                        location: Location {
+                            span: key.span,
+                            arg_spans: vec![],
+                        },
+                    });
+                    rest
+                }
+                Instruction::FunctionCall(name, args, rest) => {
+                    let arg_spans = args.iter().map(|arg| arg.span).collect();
+                    let args = args
+                        .into_iter()
+                        .map(|arg| self.evaluate(root, arg))
+                        .collect::<Result<Vec<Value>, Spanned<HandleError>>>()?;
+                    path.push(Step {
+                        segment: Segment::FunctionCall(name.node, args),
+                        location: Location {
+                            span: name.span,
+                            arg_spans,
+                        },
+                    });
+                    rest
+                }
+            };
+            next = rest.map(|rest| *rest);
+        }
+        Ok(path)
+    }
+}
+
+fn walk(
+    root: &mut dyn GameObject,
+    path: Vec<Step>,
+) -> Result<&mut dyn GameObject, Spanned<HandleError>> {
+    let mut object = root;
+    for Step { segment, location } in path {
+        object = object.child(segment).map_err(|err| location.locate(err))?;
+    }
+    Ok(object)
+}
+
+/// A path segment along with where it is in the command.
+struct Step {
+    segment: Segment,
+    location: Location,
+}
+
+struct Location {
+    span: Span,
+    arg_spans: Vec<Span>,
+}
+
+impl Location {
+    /// Points errors about a given argument at that argument, and anything else at the name.
+    fn locate(&self, error: HandleError) -> Spanned<HandleError> {
+        let span = match &error {
+            HandleError::WrongArgType(_, at, Some(_)) => self
+                .arg_spans
+                .get(*at as usize)
+                .copied()
+                .unwrap_or(self.span),
+            // Extra arguments are pointed at from the first one
+            HandleError::WrongArgCount(expected, got) if got > expected => {
+                self.arg_spans[*expected]
+            }
+            _ => self.span,
+        };
+        Spanned::new(error, span)
+    }
+}
+
+fn builtins() -> Vec<Member> {
+    vec![
+        Member::method(
+            "min",
+            vec![Param::new("values", "Int or Float...")],
+            "Smallest of the given numbers",
+        ),
+        Member::method(
+            "max",
+            vec![Param::new("values", "Int or Float...")],
+            "Largest of the given numbers",
+        ),
+        Member::method("help", vec![], "Lists the top level objects and functions"),
+        Member::method(
+            "inspect",
+            vec![Param::new("path", "Object")],
+            "Lists everything on an object, like inspect(forest.plot(0))",
+        ),
+    ]
+}
+
+fn describe(signature: String, description: &str) -> String {
+    format!("  {:<32} {}", signature, description)
+}
+
+fn help(root: &dyn GameObject) -> Value {
+    let mut lines = vec!["Objects:".to_string()];
+    lines.extend(
+        root.members()
+            .into_iter()
+            .filter(|member| matches!(member.kind, MemberKind::Child(_)))
+            .map(|member| describe(member.signature(), member.description)),
+    );
+    lines.push("Functions:".to_string());
+    lines.extend(
+        builtins()
+            .into_iter()
+            .map(|member| describe(member.signature(), member.description)),
+    );
+    lines.push("Use inspect() to see everything available at the top level".to_string());
+    Value::StringValue(lines.join("\n"))
+}
+
+fn inspect(object: &mut dyn GameObject) -> Value {
+    let mut lines = vec![format!("{}:", object.class_name())];
+    for member in object.members() {
+        let signature = match member.kind {
+            MemberKind::Property => {
+                let value = object
+                    .handle(Segment::Access(member.name.clone()))
+                    .map_or_else(|err| err.to_string(), |value| value.to_string());
+                format!("{} = {}", member.name, value)
+            }
+            _ => member.signature(),
+        };
+        lines.push(describe(signature, member.description));
+    }
+    Value::StringValue(lines.join("\n"))
+}
+
+/// Functions available everywhere, looked up before the root object.
+fn builtin(name: &str, args: &[Value]) -> Option<Result<Value, HandleError>> {
+    match name {
+        "min" => Some(extremum(args, Ordering::Less)),
+        "max" => Some(extremum(args, Ordering::Greater)),
+        _ => None,
+    }
+}
+
+/// Returns the argument that compares as `wanted` against every other one.
+fn extremum(args: &[Value], wanted: Ordering) -> Result<Value, HandleError> {
+    let mut best: Option<&Value> = None;
+    for (at, arg) in args.iter().enumerate() {
+        if arg.as_float().is_none() {
+            return Err(HandleError::WrongArgType(
+                "Int or Float".to_string(),
+                at as u16,
+                Some(arg.type_name().to_string()),
+            ));
+        }
+        if best.is_none_or(|best| compare(arg, best) == Some(wanted)) {
+            best = Some(arg);
+        }
+    }
+    best.cloned().ok_or(HandleError::WrongArgType(
+        "Int or Float".to_string(),
+        0,
+        None,
+    ))
+}
+
+fn unary(op: UnaryOp, value: Value) -> Result<Value, HandleError> {
+    match (op, value) {
+        (UnaryOp::Neg, Value::IntValue(int)) => {
+            int.checked_neg()
+                .map(Value::IntValue)
+                .ok_or(HandleError::InvalidOperation(
+                    "Integer overflow".to_string(),
+                ))
+        }
+        (UnaryOp::Neg, Value::FloatValue(float)) => Ok(Value::FloatValue(-float)),
+        (UnaryOp::Not, Value::BoolValue(bool)) => Ok(Value::BoolValue(!bool)),
+        (op, value) => Err(HandleError::InvalidOperation(format!(
+            "Cannot apply '{}' to {}",
+            op.symbol(),
+            value.type_name()
+        ))),
+    }
+}
+
+fn binary(op: BinaryOp, left: Value, right: Value) -> Result<Value, HandleError> {
+    let mismatch = || {
+        HandleError::InvalidOperation(format!(
+            "Cannot apply '{}' to {} and {}",
+            op.symbol(),
+            left.type_name(),
+            right.type_name()
+        ))
+    };
+    let compare = || compare(&left, &right).ok_or_else(mismatch);
+
+    match op {
+        BinaryOp::Eq => Ok(Value::BoolValue(equals(&left, &right))),
+        BinaryOp::NotEq => Ok(Value::BoolValue(!equals(&left, &right))),
+        BinaryOp::Less => Ok(Value::BoolValue(compare()?.is_lt())),
+        BinaryOp::LessEq => Ok(Value::BoolValue(compare()?.is_le())),
+        BinaryOp::Greater => Ok(Value::BoolValue(compare()?.is_gt())),
+        BinaryOp::GreaterEq => Ok(Value::BoolValue(compare()?.is_ge())),
+        BinaryOp::And | BinaryOp::Or => match (&left, &right) {
+            (Value::BoolValue(left), Value::BoolValue(right)) => Ok(Value::BoolValue(match op {
+                BinaryOp::And => *left && *right,
+                _ => *left || *right,
+            })),
+            _ => Err(mismatch()),
+        },
+        BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => {
+            arithmetic(op, &left, &right)?.ok_or_else(mismatch)
+        }
+    }
+}
+
+/// Applies an arithmetic operator, returning `None` when the operand types don't support it.
+fn arithmetic(op: BinaryOp, left: &Value, right: &Value) -> Result<Option<Value>, HandleError> {
+    let division_by_zero = matches!(op, BinaryOp::Div | BinaryOp::Rem)
+        && right.as_float().is_some_and(|right| right == 0.0);
+    if division_by_zero && left.as_float().is_some() {
+        return Err(HandleError::InvalidOperation(
+            "Division by zero".to_string(),
+        ));
+    }
+
+    let value = match (left, right) {
+        (Value::IntValue(left), Value::IntValue(right)) => {
+            let result = match op {
+                BinaryOp::Add => left.checked_add(*right),
+                BinaryOp::Sub => left.checked_sub(*right),
+                BinaryOp::Mul => left.checked_mul(*right),
+                BinaryOp::Div => left.checked_div(*right),
+                _ => left.checked_rem(*right),
+            };
+            Value::IntValue(result.ok_or(HandleError::InvalidOperation(
+                "Integer overflow".to_string(),
+            ))?)
+        }
+        (Value::StringValue(left), Value::StringValue(right)) if matches!(op, BinaryOp::Add) => {
+            Value::StringValue(format!("{}{}", left, right))
+        }
+        _ => {
+            let (Some(left), Some(right)) = (left.as_float(), right.as_float()) else {
+                return Ok(None);
+            };
+            Value::FloatValue(match op {
+                BinaryOp::Add => left + right,
+                BinaryOp::Sub => left - right,
+                BinaryOp::Mul => left * right,
+                BinaryOp::Div => left / right,
+                _ => left % right,
+            })
+        }
+    };
+    Ok(Some(value))
+}
+
+fn equals(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Null, Value::Null) => true,
+        (Value::BoolValue(left), Value::BoolValue(right)) => left == right,
+        (Value::StringValue(left), Value::StringValue(right)) => left == right,
+        (Value::IntValue(left), Value::IntValue(right)) => left == right,
+        _ => match (left.as_float(), right.as_float()) {
+            (Some(left), Some(right)) => left == right,
+            _ => false,
+        },
+    }
+}
+
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::IntValue(left), Value::IntValue(right)) => Some(left.cmp(right)),
+        (Value::StringValue(left), Value::StringValue(right)) => Some(left.cmp(right)),
+        _ => left.as_float()?.partial_cmp(&right.as_float()?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{forest::Forest, lexer::Lexer, parser::Parser};
+
+    /// Runs `command` like the console does, returning the last statement's value or the first
+    /// error with its span.
+    fn run(interpreter: &mut Interpreter, command: &str) -> Result<String, (String, Span)> {
+        let tokens = Lexer::tokenize(command).map_err(|err| (err.node.to_string(), err.span))?;
+        let statements = Parser::parse(tokens).map_err(|err| (err.node.to_string(), err.span))?;
+        let mut root = Forest::default();
+        let mut value = Value::Null;
+        for statement in statements {
+            value = interpreter
+                .execute(&mut root, statement)
+                .map_err(|err| (err.node.to_string(), err.span))?;
+        }
+        Ok(value.to_string())
+    }
+
+    fn eval(command: &str) -> Result<String, (String, Span)> {
+        run(&mut Interpreter::new(), command)
+    }
+
+    #[test]
+    fn multiplication_binds_tighter_than_addition() {
+        assert_eq!(eval("1 + 2 * 3"), Ok("7".to_string()));
+        assert_eq!(eval("(1 + 2) * 3"), Ok("9".to_string()));
+        assert_eq!(eval("10 - 4 - 3"), Ok("3".to_string()));
+    }
+
+    #[test]
+    fn logical_operators_short_circuit() {
+        // `x` is not defined, so evaluating it would fail
+        assert_eq!(eval("false && x"), Ok("false".to_string()));
+        assert_eq!(eval("true || x"), Ok("true".to_string()));
+        assert!(eval("true && x").is_err());
+    }
+
+    #[test]
+    fn division_by_zero_fails_on_the_operation() {
+        assert_eq!(
+            eval("1 + 1 / 0"),
+            Err(("Division by zero".to_string(), Span::new(4, 9)))
+        );
+        assert!(eval("1 % 0").is_err());
+    }
+
+    #[test]
+    fn integer_overflow_fails() {
+        assert_eq!(
+            eval("9223372036854775807 + 1"),
+            Err(("Integer overflow".to_string(), Span::new(0, 23)))
+        );
+        assert_eq!(
+            eval("-9223372036854775808"),
+            Ok("-9223372036854775808".to_string())
+        );
+    }
+
+    #[test]
+    fn leftover_tokens_are_unexpected() {
+        assert_eq!(
+            eval("1 2"),
+            Err(("Unexpected token '2'".to_string(), Span::new(2, 3)))
+        );
+    }
+
+    #[test]
+    fn unterminated_string_spans_to_the_end() {
+        let (message, span) = eval("1 + \"abc").unwrap_err();
+        assert_eq!(message, "Unterminated string");
+        assert_eq!(span, Span::new(4, 8));
+    }
+
+    #[test]
+    fn variables_are_kept_between_statements_and_commands() {
+        let mut interpreter = Interpreter::new();
+        assert_eq!(
+            run(&mut interpreter, "let x = 5; x * 2"),
+            Ok("10".to_string())
+        );
+        assert_eq!(run(&mut interpreter, "x + 1"), Ok("6".to_string()));
+        // A trailing `;` doesn't start an empty statement
+        assert_eq!(run(&mut interpreter, "let y = x;"), Ok("5".to_string()));
+    }
+
+    #[test]
+    fn members_are_reached_through_child_objects() {
+        assert_eq!(eval("plot(1).growth"), Ok("0".to_string()));
+        assert_eq!(
+            eval("plot(0).harvest()"),
+            Err(("This tree is not grown yet".to_string(), Span::new(8, 15)))
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use crate::term::{wrap_spans, Style, StyledSpan, Term, WrapMode};
+use std::io;
+
+/// Area of the terminal, in cells.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Rect {
+    pub fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Area left inside a one cell border.
+    pub fn inner(self) -> Self {
+        Self::new(
+            self.x + 1,
+            self.y + 1,
+            self.width.saturating_sub(2),
+            self.height.saturating_sub(2),
+        )
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+/// Size of one part of a split, along the split's direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Size {
+    Fixed(u16),
+    /// Percentage of the whole area being split.
+    Percent(u16),
+    /// Shares what the other sizes left, with at least that many cells.
+    Min(u16),
+}
+
+/// Splits `area` in parts of the given sizes, side by side or one above the other. Parts that
+/// don't fit are shrunk, the last ones first.
+pub fn split(area: Rect, direction: Direction, sizes: &[Size]) -> Vec<Rect> {
+    let total = match direction {
+        Direction::Horizontal => area.width,
+        Direction::Vertical => area.height,
+    };
+
+    let mut lengths: Vec<u16> = sizes
+        .iter()
+        .map(|size| match size {
+            Size::Fixed(length) => *length,
+            Size::Percent(percent) => (total as u32 * *percent as u32 / 100) as u16,
+            Size::Min(_) => 0,
+        })
+        .collect();
+    let mins: Vec<usize> = (0..sizes.len())
+        .filter(|idx| matches!(sizes[*idx], Size::Min(_)))
+        .collect();
+    let mut remaining = total.saturating_sub(lengths.iter().sum());
+    for (nth, idx) in mins.iter().enumerate() {
+        let Size::Min(min) = sizes[*idx] else {
+            unreachable!()
+        };
+        let share = remaining / (mins.len() - nth) as u16;
+        lengths[*idx] = share.max(min);
+        remaining = remaining.saturating_sub(share);
+    }
+
+    let mut offset = 0;
+    lengths
+        .into_iter()
+        .map(|length| {
+            let length = length.min(total - offset);
+            let rect = match direction {
+                Direction::Horizontal => Rect::new(area.x + offset, area.y, length, area.height),
+                Direction::Vertical => Rect::new(area.x, area.y + offset, area.width, length),
+            };
+            offset += length;
+            rect
+        })
+        .collect()
+}
+
+/// Bordered box with its title in the top border.
+pub struct Panel<'a> {
+    pub title: &'a str,
+}
+
+impl<'a> Panel<'a> {
+    pub fn new(title: &'a str) -> Self {
+        Self { title }
+    }
+
+    /// Draws the panel over `area`, returning the area left for its content.
+    pub fn draw(&self, term: &mut Term, area: Rect) -> io::Result<Rect> {
+        if area.width < 2 || area.height < 2 {
+            return Ok(Rect::default());
+        }
+        term.fill_rect(' ', area.x, area.y, area.width, area.height)?
+            .border_rect(area.x, area.y, area.width, area.height)?;
+        if !self.title.is_empty() && area.width > 2 {
+            term.print_spans(
+                &[StyledSpan::new(
+                    format!(" {} ", self.title),
+                    Style::default().bold(),
+                )],
+                area.x + 1,
+                area.y,
+                area.width - 2,
+                WrapMode::Cut,
+            )?;
+        }
+        Ok(area.inner())
+    }
+}
+
+/// Lines of text showing the newest ones at the bottom, scrolled up by `scroll` rows. Lines
+/// taller than a row once wrapped scroll one row at a time.
+pub struct TextView<I> {
+    /// Lines oldest first, only read from the newest one as far as the view needs.
+    pub lines: I,
+    pub scroll: usize,
+    pub wrap_mode: WrapMode,
+}
+
+impl<I: DoubleEndedIterator<Item = Vec<StyledSpan>>> TextView<I> {
+    pub fn new(lines: impl IntoIterator<IntoIter = I>, scroll: usize) -> Self {
+        Self {
+            lines: lines.into_iter(),
+            scroll,
+            wrap_mode: WrapMode::Cut,
+        }
+    }
+
+    pub fn wrap(mut self, wrap_mode: WrapMode) -> Self {
+        self.wrap_mode = wrap_mode;
+        self
+    }
+
+    /// Up to `count` of the last rows the lines take once wrapped `width` cells wide, oldest
+    /// first. Older lines are not wrapped at all, so long scrollbacks stay cheap.
+    fn last_rows(self, width: u16, count: usize) -> Vec<Vec<StyledSpan>> {
+        let mut rows = vec![];
+        for line in self.lines.rev() {
+            if rows.len() >= count {
+                break;
+            }
+            rows.extend(wrap_spans(&line, width, self.wrap_mode).into_iter().rev());
+        }
+        rows.reverse();
+        rows
+    }
+
+    /// Draws the view over `area`, returning its scroll clamped so that the area stays filled.
+    pub fn draw(self, term: &mut Term, area: Rect) -> io::Result<usize> {
+        if area.is_empty() {
+            return Ok(0);
+        }
+        let height = area.height as usize;
+        let scroll = self.scroll;
+        let rows = self.last_rows(area.width, scroll.saturating_add(height));
+        let scroll = scroll.min(rows.len().saturating_sub(height));
+        for (idx, row) in rows
+            .iter()
+            .rev()
+            .skip(scroll)
+            .take(height)
+            .rev()
+            .enumerate()
+        {
+            term.print_spans(row, area.x, area.y + idx as u16, area.width, WrapMode::Cut)?;
+        }
+        Ok(scroll)
+    }
+}
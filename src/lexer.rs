@@ -1,13 +1,64 @@
 use std::fmt::Display;
 
+/// Byte range of a token or node in the submitted command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Span covering both `self` and `other` and everything in between.
+    pub fn to(self, other: Span) -> Self {
+        Self::new(self.start.min(other.start), self.end.max(other.end))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(node: T, span: Span) -> Self {
+        Self { node, span }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Token {
     Keyword(String),
-    Int(i64),
+    /// Magnitude of an int, which the parser checks once it knows whether it is negated.
+    Int(u64),
+    Float(f64),
+    Bool(bool),
+    Null,
+    String(String),
     OpenParen,
     CloseParen,
     Dot,
     Coma,
+    Semicolon,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Equal,
+    EqualEqual,
+    BangEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    AndAnd,
+    OrOr,
+    Bang,
 }
 
 impl Token {
@@ -15,38 +66,60 @@ impl Token {
         match self {
             Self::Keyword(keyword) => keyword.clone(),
             Self::Int(int) => int.to_string(),
+            Self::Float(float) => format!("{:?}", float),
+            Self::Bool(bool) => bool.to_string(),
+            Self::Null => "null".to_string(),
+            Self::String(string) => format!("{:?}", string),
             Self::OpenParen => "(".to_string(),
             Self::CloseParen => ")".to_string(),
             Self::Dot => ".".to_string(),
             Self::Coma => ",".to_string(),
+            Self::Semicolon => ";".to_string(),
+            Self::Plus => "+".to_string(),
+            Self::Minus => "-".to_string(),
+            Self::Star => "*".to_string(),
+            Self::Slash => "/".to_string(),
+            Self::Percent => "%".to_string(),
+            Self::Equal => "=".to_string(),
+            Self::EqualEqual => "==".to_string(),
+            Self::BangEqual => "!=".to_string(),
+            Self::Less => "<".to_string(),
+            Self::LessEqual => "<=".to_string(),
+            Self::Greater => ">".to_string(),
+            Self::GreaterEqual => ">=".to_string(),
+            Self::AndAnd => "&&".to_string(),
+            Self::OrOr => "||".to_string(),
+            Self::Bang => "!".to_string(),
         }
     }
 }
 
 #[derive(Debug)]
 pub enum LexerError {
-    Unknown(u16, char),
-    NumberParseError(u16, String),
+    Unknown(char),
+    NumberParseError(String),
+    UnterminatedString,
+    UnknownEscape(char),
 }
 
 impl Display for LexerError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Unknown(idx, char) => write!(f, "Unexpected char '{}' at {}", char, idx),
-            Self::NumberParseError(idx, number) => {
-                write!(f, "Number '{}' failed to parse at {}", number, idx)
-            }
+            Self::Unknown(char) => write!(f, "Unexpected char '{}'", char),
+            Self::NumberParseError(number) => write!(f, "Number '{}' failed to parse", number),
+            Self::UnterminatedString => write!(f, "Unterminated string"),
+            Self::UnknownEscape(char) => write!(f, "Unknown escape sequence '\\{}'", char),
         }
     }
 }
 
 pub struct Lexer {
     source: String,
-    pointer: u16,
+    pointer: usize,
 }
 
 impl Lexer {
-    pub fn tokenize(source: &str) -> Result<Vec<Token>, LexerError> {
+    pub fn tokenize(source: &str) -> Result<Vec<Spanned<Token>>, Spanned<LexerError>> {
         let mut lexer = Self {
             source: source.to_string(),
             pointer: 0,
@@ -56,58 +129,164 @@ impl Lexer {
     }
 
     fn peek(&self) -> Option<char> {
-        self.source.chars().nth(self.pointer as usize)
+        self.source[self.pointer..].chars().next()
+    }
+
+    fn peek_next(&self) -> Option<char> {
+        self.source[self.pointer..].chars().nth(1)
     }
 
     fn consume(&mut self) -> Option<char> {
-        let char = self.source.chars().nth(self.pointer as usize);
-        self.pointer += 1;
-        char
+        let char = self.peek()?;
+        self.pointer += char.len_utf8();
+        Some(char)
+    }
+
+    fn consume_if(&mut self, expected: char) -> bool {
+        if self.peek() != Some(expected) {
+            return false;
+        }
+        self.pointer += expected.len_utf8();
+        true
+    }
+
+    /// Consumes chars while they match `predicate`, pushing them to `buffer`.
+    fn consume_while(&mut self, buffer: &mut String, predicate: impl Fn(char) -> bool) {
+        while let Some(char) = self.peek() {
+            if !predicate(char) {
+                break;
+            }
+            self.pointer += char.len_utf8();
+            buffer.push(char);
+        }
     }
 
-    fn tokens(&mut self) -> Result<Vec<Token>, LexerError> {
+    fn error(&self, error: LexerError, start: usize) -> Spanned<LexerError> {
+        Spanned::new(error, Span::new(start, self.pointer))
+    }
+
+    fn tokens(&mut self) -> Result<Vec<Spanned<Token>>, Spanned<LexerError>> {
         let mut tokens = vec![];
 
-        while let Some(char) = self.consume() {
-            if char.is_whitespace() {
+        loop {
+            let start = self.pointer;
+            let Some(char) = self.consume() else {
+                break;
+            };
+            let token = if char.is_whitespace() {
                 continue;
             } else if char.is_alphabetic() {
                 let mut buffer = String::from(char);
-                while let Some(char) = self.peek() {
-                    if !char.is_alphanumeric() && char != '_' {
-                        break;
-                    }
-                    self.pointer += 1;
-                    buffer.push(char);
+                self.consume_while(&mut buffer, |char| char.is_alphanumeric() || char == '_');
+                match buffer.as_str() {
+                    "true" => Token::Bool(true),
+                    "false" => Token::Bool(false),
+                    "null" => Token::Null,
+                    _ => Token::Keyword(buffer),
                 }
-                tokens.push(Token::Keyword(buffer));
-            } else if char.is_ascii_digit() || char == '-' {
-                let number_start = self.pointer - 1;
+            } else if char.is_ascii_digit() {
+                // Digits may be grouped with `_`, e.g. `1_000`, which is stripped before parsing
                 let mut buffer = String::from(char);
-                while let Some(char) = self.peek() {
-                    if !char.is_numeric() && char != '_' {
-                        break;
-                    }
+                self.consume_while(&mut buffer, |char| char.is_numeric() || char == '_');
+                if self.peek() == Some('.') && self.peek_next().is_some_and(|c| c.is_ascii_digit())
+                {
                     self.pointer += 1;
-                    buffer.push(char);
+                    buffer.push('.');
+                    self.consume_while(&mut buffer, |char| char.is_numeric() || char == '_');
+                    let float = buffer.replace('_', "").parse::<f64>().map_err(|_| {
+                        self.error(LexerError::NumberParseError(buffer.clone()), start)
+                    })?;
+                    Token::Float(float)
+                } else {
+                    let int = buffer.replace('_', "").parse::<u64>().map_err(|_| {
+                        self.error(LexerError::NumberParseError(buffer.clone()), start)
+                    })?;
+                    Token::Int(int)
                 }
-                let int = buffer
-                    .parse::<i64>()
-                    .map_err(|_| LexerError::NumberParseError(number_start, buffer))?;
-                tokens.push(Token::Int(int));
+            } else if char == '"' {
+                Token::String(self.string(start)?)
             } else if char == '(' {
-                tokens.push(Token::OpenParen);
+                Token::OpenParen
             } else if char == ')' {
-                tokens.push(Token::CloseParen);
+                Token::CloseParen
             } else if char == '.' {
-                tokens.push(Token::Dot);
+                Token::Dot
             } else if char == ',' {
-                tokens.push(Token::Coma);
+                Token::Coma
+            } else if char == ';' {
+                Token::Semicolon
+            } else if char == '+' {
+                Token::Plus
+            } else if char == '-' {
+                Token::Minus
+            } else if char == '*' {
+                Token::Star
+            } else if char == '/' {
+                Token::Slash
+            } else if char == '%' {
+                Token::Percent
+            } else if char == '=' {
+                if self.consume_if('=') {
+                    Token::EqualEqual
+                } else {
+                    Token::Equal
+                }
+            } else if char == '!' {
+                if self.consume_if('=') {
+                    Token::BangEqual
+                } else {
+                    Token::Bang
+                }
+            } else if char == '<' {
+                if self.consume_if('=') {
+                    Token::LessEqual
+                } else {
+                    Token::Less
+                }
+            } else if char == '>' {
+                if self.consume_if('=') {
+                    Token::GreaterEqual
+                } else {
+                    Token::Greater
+                }
+            } else if char == '&' && self.consume_if('&') {
+                Token::AndAnd
+            } else if char == '|' && self.consume_if('|') {
+                Token::OrOr
             } else {
-                return Err(LexerError::Unknown(self.pointer - 1, char));
-            }
+                return Err(self.error(LexerError::Unknown(char), start));
+            };
+            tokens.push(Spanned::new(token, Span::new(start, self.pointer)));
         }
 
         Ok(tokens)
     }
+
+    fn string(&mut self, string_start: usize) -> Result<String, Spanned<LexerError>> {
+        let mut buffer = String::new();
+        loop {
+            let char = self
+                .consume()
+                .ok_or(self.error(LexerError::UnterminatedString, string_start))?;
+            match char {
+                '"' => return Ok(buffer),
+                '\\' => {
+                    let escape_start = self.pointer - 1;
+                    let escape = self
+                        .consume()
+                        .ok_or(self.error(LexerError::UnterminatedString, string_start))?;
+                    buffer.push(match escape {
+                        '"' => '"',
+                        '\\' => '\\',
+                        'n' => '\n',
+                        't' => '\t',
+                        _ => {
+                            return Err(self.error(LexerError::UnknownEscape(escape), escape_start))
+                        }
+                    });
+                }
+                _ => buffer.push(char),
+            }
+        }
+    }
 }
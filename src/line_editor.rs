@@ -0,0 +1,406 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Editable text with a cursor and emacs style keybindings, spanning several lines once a
+/// newline is inserted.
+#[derive(Clone, Default)]
+pub struct LineEditor {
+    buffer: String,
+    /// Byte index of the cursor in `buffer`, always on a grapheme boundary.
+    cursor: usize,
+    /// Last text removed by a kill command, inserted back by yank.
+    kill_buffer: String,
+}
+
+impl LineEditor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.buffer
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.buffer.split('\n')
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines().count()
+    }
+
+    /// Line of the cursor and its terminal column relative to the start of that line.
+    pub fn cursor_position(&self) -> (usize, usize) {
+        let start = self.line_start(self.cursor);
+        (
+            self.buffer[..start].matches('\n').count(),
+            self.buffer[start..self.cursor].width(),
+        )
+    }
+
+    pub fn newline(&mut self) {
+        self.buffer.insert(self.cursor, '\n');
+        self.cursor += 1;
+    }
+
+    /// Moves the cursor to the line above, returning false when already on the first line.
+    pub fn line_up(&mut self) -> bool {
+        let start = self.line_start(self.cursor);
+        if start == 0 {
+            return false;
+        }
+        let column = self.cursor_position().1;
+        self.cursor = self.column_index(self.line_start(start - 1), column);
+        true
+    }
+
+    /// Moves the cursor to the line below, returning false when already on the last line.
+    pub fn line_down(&mut self) -> bool {
+        let end = self.line_end(self.cursor);
+        if end == self.buffer.len() {
+            return false;
+        }
+        let column = self.cursor_position().1;
+        self.cursor = self.column_index(end + 1, column);
+        true
+    }
+
+    /// Replaces the whole line, putting the cursor at its end.
+    pub fn set(&mut self, text: &str) {
+        self.buffer = text.to_string();
+        self.cursor = self.buffer.len();
+    }
+
+    /// Empties the line, returning what it contained.
+    pub fn take(&mut self) -> String {
+        self.cursor = 0;
+        std::mem::take(&mut self.buffer)
+    }
+
+    /// Replaces `range` with `text`, putting the cursor right after it.
+    pub fn replace_range(&mut self, range: Range<usize>, text: &str) {
+        self.buffer.replace_range(range.clone(), text);
+        self.cursor = range.start + text.len();
+    }
+
+    /// Applies the editing action bound to `key`, returning whether there was one.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char('a') if ctrl => self.home(),
+            KeyCode::Char('e') if ctrl => self.end(),
+            KeyCode::Char('b') if ctrl => self.left(),
+            KeyCode::Char('f') if ctrl => self.right(),
+            KeyCode::Char('w') if ctrl => self.kill_word_before(),
+            KeyCode::Char('u') if ctrl => self.kill_to_start(),
+            KeyCode::Char('k') if ctrl => self.kill_to_end(),
+            KeyCode::Char('y') if ctrl => self.yank(),
+            KeyCode::Char('b') if alt => self.word_left(),
+            KeyCode::Char('f') if alt => self.word_right(),
+            KeyCode::Char(_) if ctrl || alt => return false,
+            KeyCode::Char(char) => self.insert(char),
+            KeyCode::Left => self.left(),
+            KeyCode::Right => self.right(),
+            KeyCode::Home => self.home(),
+            KeyCode::End => self.end(),
+            KeyCode::Backspace => self.remove_before(),
+            KeyCode::Delete => self.remove_at(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Start of the line `idx` is on.
+    fn line_start(&self, idx: usize) -> usize {
+        self.buffer[..idx]
+            .rfind('\n')
+            .map_or(0, |newline| newline + 1)
+    }
+
+    fn line_end(&self, idx: usize) -> usize {
+        self.buffer[idx..]
+            .find('\n')
+            .map_or(self.buffer.len(), |newline| idx + newline)
+    }
+
+    /// Byte index of the grapheme at `column` on the line starting at `start`, or of the line's
+    /// end if it is shorter.
+    fn column_index(&self, start: usize, column: usize) -> usize {
+        let mut width = 0;
+        for (idx, grapheme) in self.buffer[start..self.line_end(start)].grapheme_indices(true) {
+            width += grapheme.width();
+            if width > column {
+                return start + idx;
+            }
+        }
+        self.line_end(start)
+    }
+
+    /// Start of the grapheme before `idx`, so that e.g. an accented letter made of two chars
+    /// is stepped over at once.
+    fn previous_boundary(&self, idx: usize) -> usize {
+        self.buffer[..idx]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(start, _)| start)
+    }
+
+    fn next_boundary(&self, idx: usize) -> usize {
+        self.buffer[idx..]
+            .graphemes(true)
+            .next()
+            .map_or(idx, |grapheme| idx + grapheme.len())
+    }
+
+    /// Start of the word before `idx`, words being made of alphanumeric chars and `_`.
+    fn word_start(&self, idx: usize) -> usize {
+        let is_word = |char: char| char.is_alphanumeric() || char == '_';
+        let before = &self.buffer[..idx];
+        let end = before.trim_end_matches(|char| !is_word(char)).len();
+        before[..end].trim_end_matches(is_word).len()
+    }
+
+    /// End of the word after `idx`.
+    fn word_end(&self, idx: usize) -> usize {
+        let is_word = |char: char| char.is_alphanumeric() || char == '_';
+        let after = &self.buffer[idx..];
+        let start = after.len() - after.trim_start_matches(|char| !is_word(char)).len();
+        idx + after.len() - after[start..].trim_start_matches(is_word).len()
+    }
+
+    fn insert(&mut self, char: char) {
+        if char.is_control() {
+            return;
+        }
+        self.buffer.insert(self.cursor, char);
+        self.cursor += char.len_utf8();
+        // A combining char joins the grapheme before it, which the cursor must stay after
+        self.cursor = self.next_boundary(self.previous_boundary(self.cursor));
+    }
+
+    fn left(&mut self) {
+        self.cursor = self.previous_boundary(self.cursor);
+    }
+
+    fn right(&mut self) {
+        self.cursor = self.next_boundary(self.cursor);
+    }
+
+    fn home(&mut self) {
+        self.cursor = self.line_start(self.cursor);
+    }
+
+    fn end(&mut self) {
+        self.cursor = self.line_end(self.cursor);
+    }
+
+    fn word_left(&mut self) {
+        self.cursor = self.word_start(self.cursor);
+    }
+
+    fn word_right(&mut self) {
+        self.cursor = self.word_end(self.cursor);
+    }
+
+    fn remove_before(&mut self) {
+        let start = self.previous_boundary(self.cursor);
+        self.buffer.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    fn remove_at(&mut self) {
+        let end = self.next_boundary(self.cursor);
+        self.buffer.replace_range(self.cursor..end, "");
+    }
+
+    /// Removes `range` from the line, keeping it to be yanked back.
+    fn kill(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        self.kill_buffer = self.buffer[range.clone()].to_string();
+        self.buffer.replace_range(range.clone(), "");
+        self.cursor = range.start;
+    }
+
+    /// Kills back to the previous whitespace, like a shell's Ctrl+W.
+    fn kill_word_before(&mut self) {
+        let before = self.buffer[..self.cursor].trim_end();
+        let start = before
+            .trim_end_matches(|char: char| !char.is_whitespace())
+            .len();
+        self.kill(start..self.cursor);
+    }
+
+    fn kill_to_start(&mut self) {
+        self.kill(self.line_start(self.cursor)..self.cursor);
+    }
+
+    /// Kills to the end of the line, or joins the next line when already there.
+    fn kill_to_end(&mut self) {
+        let end = self.line_end(self.cursor);
+        if end == self.cursor {
+            self.kill(self.cursor..self.next_boundary(self.cursor));
+        } else {
+            self.kill(self.cursor..end);
+        }
+    }
+
+    fn yank(&mut self) {
+        let text = self.kill_buffer.clone();
+        self.replace_range(self.cursor..self.cursor, &text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(text: &str, cursor: usize) -> LineEditor {
+        let mut editor = LineEditor::new();
+        editor.set(text);
+        editor.cursor = cursor;
+        editor
+    }
+
+    fn press(editor: &mut LineEditor, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        editor.handle_key(KeyEvent::new(code, modifiers))
+    }
+
+    fn ctrl(editor: &mut LineEditor, char: char) -> bool {
+        press(editor, KeyCode::Char(char), KeyModifiers::CONTROL)
+    }
+
+    fn alt(editor: &mut LineEditor, char: char) -> bool {
+        press(editor, KeyCode::Char(char), KeyModifiers::ALT)
+    }
+
+    #[test]
+    fn ctrl_w_kills_back_to_whitespace() {
+        let mut editor = editor("let logs = forest.plot(2).logs  ", 32);
+        assert!(ctrl(&mut editor, 'w'));
+        assert_eq!(editor.text(), "let logs = ");
+        assert_eq!(editor.cursor(), 11);
+        ctrl(&mut editor, 'w');
+        assert_eq!(editor.text(), "let logs ");
+        ctrl(&mut editor, 'w');
+        ctrl(&mut editor, 'w');
+        assert_eq!(editor.text(), "");
+        ctrl(&mut editor, 'w');
+        assert_eq!(editor.text(), "");
+    }
+
+    #[test]
+    fn ctrl_u_then_ctrl_y_yanks_the_start_back() {
+        let mut editor = editor("coins + 1", 5);
+        ctrl(&mut editor, 'u');
+        assert_eq!(editor.text(), " + 1");
+        assert_eq!(editor.cursor(), 0);
+        ctrl(&mut editor, 'e');
+        ctrl(&mut editor, 'y');
+        assert_eq!(editor.text(), " + 1coins");
+        assert_eq!(editor.cursor(), 9);
+    }
+
+    #[test]
+    fn ctrl_k_then_ctrl_y_yanks_the_end_back() {
+        let mut editor = editor("coins + 1", 5);
+        ctrl(&mut editor, 'k');
+        assert_eq!(editor.text(), "coins");
+        assert_eq!(editor.cursor(), 5);
+        ctrl(&mut editor, 'a');
+        ctrl(&mut editor, 'y');
+        assert_eq!(editor.text(), " + 1coins");
+        assert_eq!(editor.cursor(), 4);
+        // Yanking again inserts the same text
+        ctrl(&mut editor, 'y');
+        assert_eq!(editor.text(), " + 1 + 1coins");
+    }
+
+    #[test]
+    fn ctrl_k_kills_to_the_end_of_the_line_then_joins_the_next() {
+        let mut editor = editor("min(1,\n2)", 4);
+        ctrl(&mut editor, 'k');
+        assert_eq!(editor.text(), "min(\n2)");
+        ctrl(&mut editor, 'k');
+        assert_eq!(editor.text(), "min(2)");
+        ctrl(&mut editor, 'y');
+        assert_eq!(editor.text(), "min(\n2)");
+    }
+
+    #[test]
+    fn alt_b_and_f_skip_punctuation() {
+        let mut editor = editor("forest.plot(2).harvest()", 24);
+        for expected in [15, 12, 7, 0, 0] {
+            assert!(alt(&mut editor, 'b'));
+            assert_eq!(editor.cursor(), expected);
+        }
+        for expected in [6, 11, 13, 22, 24, 24] {
+            assert!(alt(&mut editor, 'f'));
+            assert_eq!(editor.cursor(), expected);
+        }
+    }
+
+    #[test]
+    fn home_and_end_stay_on_the_cursor_line() {
+        let mut editor = editor("let a = 1\na + 2", 12);
+        press(&mut editor, KeyCode::Home, KeyModifiers::NONE);
+        assert_eq!(editor.cursor(), 10);
+        press(&mut editor, KeyCode::End, KeyModifiers::NONE);
+        assert_eq!(editor.cursor(), 15);
+        ctrl(&mut editor, 'a');
+        assert_eq!(editor.cursor(), 10);
+        editor.cursor = 4;
+        ctrl(&mut editor, 'e');
+        assert_eq!(editor.cursor(), 9);
+        press(&mut editor, KeyCode::Home, KeyModifiers::NONE);
+        assert_eq!(editor.cursor(), 0);
+    }
+
+    #[test]
+    fn backspace_and_delete_at_the_edges() {
+        let mut editor = editor("coins", 0);
+        press(&mut editor, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(editor.text(), "coins");
+        assert_eq!(editor.cursor(), 0);
+        press(&mut editor, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!(editor.text(), "oins");
+        assert_eq!(editor.cursor(), 0);
+
+        editor.cursor = 4;
+        press(&mut editor, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!(editor.text(), "oins");
+        press(&mut editor, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(editor.text(), "oin");
+        assert_eq!(editor.cursor(), 3);
+
+        let mut empty = LineEditor::new();
+        press(&mut empty, KeyCode::Backspace, KeyModifiers::NONE);
+        press(&mut empty, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!(empty.text(), "");
+        assert_eq!(empty.cursor(), 0);
+    }
+
+    #[test]
+    fn backspace_removes_a_whole_grapheme() {
+        let mut editor = editor("cafe\u{301}", 6);
+        press(&mut editor, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(editor.text(), "caf");
+        assert_eq!(editor.cursor(), 3);
+    }
+
+    #[test]
+    fn unbound_keys_are_not_handled() {
+        let mut editor = editor("coins", 5);
+        assert!(!ctrl(&mut editor, 'r'));
+        assert!(!press(&mut editor, KeyCode::Tab, KeyModifiers::NONE));
+        assert_eq!(editor.text(), "coins");
+    }
+}
@@ -0,0 +1,323 @@
+use crate::{
+    game_object::{arg_count, string_arg, GameObject, HandleError, Member, Param, Segment, Value},
+    highlight::prompt_lines,
+    lexer::{Span, Spanned},
+    term::{Style, StyledSpan},
+};
+use crossterm::style::Color;
+use std::{
+    cmp,
+    collections::VecDeque,
+    fs,
+    time::{Duration, Instant},
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Number of entries of each kind kept, older ones being dropped as new ones come in.
+const LOG_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Echo,
+    Result,
+    Error,
+    Notice,
+}
+
+impl EntryKind {
+    const ALL: [Self; 4] = [Self::Echo, Self::Result, Self::Error, Self::Notice];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Echo => "echo",
+            Self::Result => "result",
+            Self::Error => "error",
+            Self::Notice => "notice",
+        }
+    }
+
+    fn from_name(name: &str) -> Result<Self, HandleError> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name() == name)
+            .ok_or_else(|| {
+                HandleError::Failed(format!(
+                    "Unknown log entry kind '{}', expected 'echo', 'result', 'error' or 'notice'",
+                    name
+                ))
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Entry {
+    /// A submitted command, with its highlights and the span of its error if it failed.
+    Echo {
+        command: String,
+        highlights: Vec<Spanned<Style>>,
+        error: Option<Span>,
+    },
+    Result(Value),
+    Error(String),
+    /// Something that happened in the game rather than in response to a command.
+    Notice(String),
+}
+
+impl Entry {
+    pub fn kind(&self) -> EntryKind {
+        match self {
+            Self::Echo { .. } => EntryKind::Echo,
+            Self::Result(_) => EntryKind::Result,
+            Self::Error(_) => EntryKind::Error,
+            Self::Notice(_) => EntryKind::Notice,
+        }
+    }
+
+    /// Unstyled text of the entry, as exported and searched.
+    pub fn text(&self) -> String {
+        match self {
+            Self::Echo { command, .. } => format!("~ {}", command),
+            Self::Result(value) => value.to_string(),
+            Self::Error(message) | Self::Notice(message) => message.clone(),
+        }
+    }
+
+    pub fn lines(&self) -> Vec<Vec<StyledSpan>> {
+        let style = match self {
+            Self::Echo {
+                command,
+                highlights,
+                error,
+            } => {
+                let mut lines = prompt_lines(command, highlights);
+                if let Some(span) = error {
+                    let (row, caret) = caret(command, *span);
+                    lines.insert(row + 1, caret);
+                }
+                return lines;
+            }
+            Self::Result(_) => Style::fg(Color::Green),
+            Self::Error(_) => Style::fg(Color::Red),
+            Self::Notice(_) => Style::fg(Color::DarkGrey),
+        };
+        self.text()
+            .split('\n')
+            .map(|line| vec![StyledSpan::new(line, style)])
+            .collect()
+    }
+}
+
+/// Line of `command` where `span` starts, and a line underlining the span below it.
+fn caret(command: &str, span: Span) -> (usize, Vec<StyledSpan>) {
+    // Spans are byte offsets and may point right past the end of the command
+    let start = span.start.min(command.len());
+    let line_start = command[..start]
+        .rfind('\n')
+        .map_or(0, |newline| newline + 1);
+    let line_end = command[start..]
+        .find('\n')
+        .map_or(command.len(), |newline| start + newline);
+    let end = span.end.clamp(start, line_end);
+    (
+        command[..start].matches('\n').count(),
+        vec![
+            StyledSpan::plain(" ".repeat(2 + command[line_start..start].width())),
+            StyledSpan::new(
+                "^".repeat(cmp::max(command[start..end].width(), 1)),
+                Style::fg(Color::Red).bold(),
+            ),
+        ],
+    )
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub entry: Entry,
+    /// Time since the start of the session.
+    pub time: Duration,
+}
+
+impl LogEntry {
+    fn timestamp(&self) -> String {
+        let seconds = self.time.as_secs();
+        format!(
+            "[{:02}:{:02}:{:02}]",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    }
+}
+
+/// Output of the console, reachable from commands as `log`.
+#[derive(Clone)]
+pub struct Log {
+    entries: VecDeque<LogEntry>,
+    start: Instant,
+    hidden: Vec<EntryKind>,
+}
+
+impl Log {
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            start: Instant::now(),
+            hidden: vec![],
+        }
+    }
+
+    pub fn push(&mut self, entry: Entry) {
+        let kind = entry.kind();
+        self.entries.push_back(LogEntry {
+            entry,
+            time: self.start.elapsed(),
+        });
+        // Kinds are capped separately so that notices, pushed every tick, never evict commands
+        let of_kind = |log_entry: &LogEntry| log_entry.entry.kind() == kind;
+        if self
+            .entries
+            .iter()
+            .filter(|log_entry| of_kind(log_entry))
+            .count()
+            > LOG_SIZE
+        {
+            if let Some(oldest) = self.entries.iter().position(of_kind) {
+                self.entries.remove(oldest);
+            }
+        }
+    }
+
+    /// Time since the start of the session, as recorded with new entries.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Marks the last echoed command as failed at `span`.
+    pub fn set_error(&mut self, span: Span) {
+        let echo = self
+            .entries
+            .iter_mut()
+            .rev()
+            .find_map(|log_entry| match &mut log_entry.entry {
+                Entry::Echo { error, .. } => Some(error),
+                _ => None,
+            });
+        if let Some(error) = echo {
+            *error = Some(span);
+        }
+    }
+
+    fn visible(&self) -> impl DoubleEndedIterator<Item = &LogEntry> {
+        self.entries
+            .iter()
+            .filter(|log_entry| !self.hidden.contains(&log_entry.entry.kind()))
+    }
+
+    /// Styled lines of the entries of `kinds` pushed from `since` on that are not hidden, oldest
+    /// first. Entries are only styled once reached, so reading from the newest end skips the
+    /// rest of the log.
+    pub fn lines<'a>(
+        &'a self,
+        kinds: &'a [EntryKind],
+        since: Duration,
+    ) -> impl DoubleEndedIterator<Item = Vec<StyledSpan>> + 'a {
+        self.visible()
+            .filter(move |log_entry| {
+                kinds.contains(&log_entry.entry.kind()) && log_entry.time >= since
+            })
+            .flat_map(|log_entry| log_entry.entry.lines())
+    }
+
+    fn export(&self, path: &str) -> Result<usize, HandleError> {
+        let text: Vec<String> = self
+            .entries
+            .iter()
+            .map(|log_entry| format!("{} {}", log_entry.timestamp(), log_entry.entry.text()))
+            .collect();
+        fs::write(path, text.join("\n"))
+            .map_err(|err| HandleError::Failed(format!("Failed to export log: {}", err)))?;
+        Ok(text.len())
+    }
+
+    fn search(&self, query: &str) -> Vec<String> {
+        self.visible()
+            .filter(|log_entry| log_entry.entry.text().contains(query))
+            .map(|log_entry| format!("{} {}", log_entry.timestamp(), log_entry.entry.text()))
+            .collect()
+    }
+}
+
+impl GameObject for Log {
+    fn class_name(&self) -> String {
+        "Log".to_string()
+    }
+
+    fn members(&self) -> Vec<Member> {
+        vec![
+            Member::property("entries", "Number of entries in the log"),
+            Member::method(
+                "hide",
+                vec![Param::new("kind", "String")],
+                "Hides 'echo', 'result', 'error' or 'notice' entries",
+            ),
+            Member::method(
+                "show",
+                vec![Param::new("kind", "String")],
+                "Shows entries of a hidden kind again",
+            ),
+            Member::method(
+                "search",
+                vec![Param::new("query", "String")],
+                "Lists the visible entries containing the query",
+            ),
+            Member::method(
+                "export",
+                vec![Param::new("path", "String")],
+                "Writes the whole log to a file, returning the number of entries",
+            ),
+            Member::method("clear", vec![], "Removes every entry"),
+        ]
+    }
+
+    fn handle(&mut self, segment: Segment) -> Result<Value, HandleError> {
+        match segment {
+            Segment::Access(key) if key == "entries" => {
+                Ok(Value::IntValue(self.entries.len() as i64))
+            }
+            Segment::FunctionCall(name, args) if name == "hide" => {
+                arg_count(&args, 1)?;
+                let kind = EntryKind::from_name(string_arg(&args, 0)?)?;
+                if !self.hidden.contains(&kind) {
+                    self.hidden.push(kind);
+                }
+                Ok(Value::Null)
+            }
+            Segment::FunctionCall(name, args) if name == "show" => {
+                arg_count(&args, 1)?;
+                let kind = EntryKind::from_name(string_arg(&args, 0)?)?;
+                self.hidden.retain(|hidden| *hidden != kind);
+                Ok(Value::Null)
+            }
+            Segment::FunctionCall(name, args) if name == "search" => {
+                arg_count(&args, 1)?;
+                Ok(Value::StringValue(
+                    self.search(string_arg(&args, 0)?).join("\n"),
+                ))
+            }
+            Segment::FunctionCall(name, args) if name == "export" => {
+                arg_count(&args, 1)?;
+                Ok(Value::IntValue(self.export(string_arg(&args, 0)?)? as i64))
+            }
+            Segment::FunctionCall(name, args) if name == "clear" => {
+                arg_count(&args, 0)?;
+                self.clear();
+                Ok(Value::Null)
+            }
+            _ => self.return_err(segment),
+        }
+    }
+}
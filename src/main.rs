@@ -3,10 +3,19 @@ use std::{
     time::{Duration, Instant},
 };
 
+mod completion;
+mod forest;
 mod game;
 mod game_object;
+mod highlight;
+mod history;
+mod interpreter;
+mod layout;
 mod lexer;
+mod line_editor;
+mod log;
 mod parser;
+mod status;
 mod term;
 use game::Game;
 
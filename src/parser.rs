@@ -1,16 +1,121 @@
 use std::fmt::Display;
 
-use crate::lexer::Token;
+use crate::lexer::{Span, Spanned, Token};
 
 #[derive(Debug)]
-pub enum Arg {
+pub enum Literal {
     Int(i64),
+    Float(f64),
+    Bool(bool),
+    Null,
+    String(String),
 }
 
 #[derive(Debug)]
 pub enum Instruction {
-    Access(String, Option<Box<Instruction>>),
-    FunctionCall(String, Vec<Arg>),
+    Access(Spanned<String>, Option<Box<Instruction>>),
+    FunctionCall(
+        Spanned<String>,
+        Vec<Spanned<Expression>>,
+        Option<Box<Instruction>>,
+    ),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+impl UnaryOp {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Neg => "-",
+            Self::Not => "!",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    NotEq,
+    Less,
+    LessEq,
+    Greater,
+    GreaterEq,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    fn from_token(token: &Token) -> Option<Self> {
+        match token {
+            Token::Plus => Some(Self::Add),
+            Token::Minus => Some(Self::Sub),
+            Token::Star => Some(Self::Mul),
+            Token::Slash => Some(Self::Div),
+            Token::Percent => Some(Self::Rem),
+            Token::EqualEqual => Some(Self::Eq),
+            Token::BangEqual => Some(Self::NotEq),
+            Token::Less => Some(Self::Less),
+            Token::LessEqual => Some(Self::LessEq),
+            Token::Greater => Some(Self::Greater),
+            Token::GreaterEqual => Some(Self::GreaterEq),
+            Token::AndAnd => Some(Self::And),
+            Token::OrOr => Some(Self::Or),
+            _ => None,
+        }
+    }
+
+    /// Binding strength of the operator, higher binds tighter.
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Or => 1,
+            Self::And => 2,
+            Self::Eq | Self::NotEq => 3,
+            Self::Less | Self::LessEq | Self::Greater | Self::GreaterEq => 4,
+            Self::Add | Self::Sub => 5,
+            Self::Mul | Self::Div | Self::Rem => 6,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Rem => "%",
+            Self::Eq => "==",
+            Self::NotEq => "!=",
+            Self::Less => "<",
+            Self::LessEq => "<=",
+            Self::Greater => ">",
+            Self::GreaterEq => ">=",
+            Self::And => "&&",
+            Self::Or => "||",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Expression {
+    Literal(Literal),
+    Instruction(Instruction),
+    Unary(UnaryOp, Box<Spanned<Expression>>),
+    Binary(Box<Spanned<Expression>>, BinaryOp, Box<Spanned<Expression>>),
+}
+
+#[derive(Debug)]
+pub enum Statement {
+    Let(Spanned<String>, Spanned<Expression>),
+    Expression(Spanned<Expression>),
 }
 
 #[derive(Debug)]
@@ -18,6 +123,8 @@ pub enum ParserError {
     UnexpectedToken(Option<Token>),
     ExpectedToken,
     Expected(Token, Option<Token>),
+    /// An int literal out of the range of `i64`, with its sign.
+    IntOutOfRange(String),
 }
 
 impl Display for ParserError {
@@ -38,71 +145,206 @@ impl Display for ParserError {
                 got.clone()
                     .map_or("none".to_string(), |token| format!("'{}'", token.text()))
             ),
+            Self::IntOutOfRange(int) => write!(f, "Number '{}' does not fit in an Int", int),
         }
     }
 }
 
 pub struct Parser {
-    tokens: Vec<Token>,
-    pointer: u16,
+    tokens: Vec<Spanned<Token>>,
+    pointer: usize,
 }
 
 impl Parser {
-    pub fn parse(tokens: Vec<Token>) -> Result<Instruction, ParserError> {
+    pub fn parse(tokens: Vec<Spanned<Token>>) -> Result<Vec<Statement>, Spanned<ParserError>> {
         let mut parser = Self { tokens, pointer: 0 };
 
-        parser.parse_instruction()
+        let mut statements = vec![parser.parse_statement()?];
+        while let Some(token) = parser.peek() {
+            let Token::Semicolon = token else {
+                return Err(parser.error(ParserError::UnexpectedToken(Some(token))));
+            };
+            parser.pointer += 1;
+            // A trailing `;` doesn't start a new statement
+            if parser.peek().is_some() {
+                statements.push(parser.parse_statement()?);
+            }
+        }
+        Ok(statements)
     }
 
     fn peek(&self) -> Option<Token> {
-        self.tokens.get(self.pointer as usize).cloned()
+        self.tokens
+            .get(self.pointer)
+            .map(|token| token.node.clone())
+    }
+
+    /// Span of the next token, or a one byte span just past the input when there is none.
+    fn span(&self) -> Span {
+        match self.tokens.get(self.pointer) {
+            Some(token) => token.span,
+            None => self.tokens.last().map_or(Span::new(0, 1), |token| {
+                Span::new(token.span.end, token.span.end + 1)
+            }),
+        }
     }
 
-    fn consume(&mut self) -> Option<Token> {
-        let token = self.tokens.get(self.pointer as usize);
+    fn previous_span(&self) -> Span {
+        self.tokens[self.pointer - 1].span
+    }
+
+    fn error(&self, error: ParserError) -> Spanned<ParserError> {
+        Spanned::new(error, self.span())
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), Spanned<ParserError>> {
+        match self.peek() {
+            Some(token) if std::mem::discriminant(&token) == std::mem::discriminant(&expected) => {
+                self.pointer += 1;
+                Ok(())
+            }
+            token => Err(self.error(ParserError::Expected(expected, token))),
+        }
+    }
+
+    fn parse_keyword(&mut self) -> Result<Spanned<String>, Spanned<ParserError>> {
+        match self.peek() {
+            Some(Token::Keyword(keyword)) => {
+                self.pointer += 1;
+                Ok(Spanned::new(keyword, self.previous_span()))
+            }
+            Some(token) => Err(self.error(ParserError::UnexpectedToken(Some(token)))),
+            None => Err(self.error(ParserError::ExpectedToken)),
+        }
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement, Spanned<ParserError>> {
+        let Some(Token::Keyword(keyword)) = self.peek() else {
+            return Ok(Statement::Expression(self.parse_expression()?));
+        };
+        if keyword != "let" {
+            return Ok(Statement::Expression(self.parse_expression()?));
+        }
         self.pointer += 1;
-        token.cloned()
+        let name = self.parse_keyword()?;
+        self.expect(Token::Equal)?;
+        Ok(Statement::Let(name, self.parse_expression()?))
     }
 
-    fn parse_instruction(&mut self) -> Result<Instruction, ParserError> {
-        let token = self.consume().ok_or(ParserError::ExpectedToken)?;
-        match token {
-            Token::Keyword(keyword) => match self.peek() {
-                Some(Token::OpenParen) => {
-                    self.pointer += 1;
-                    let mut args: Vec<Arg> = vec![];
-                    while let Some(token) = self.peek() {
-                        if let Token::CloseParen = token {
-                            break;
-                        }
-                        self.pointer += 1;
-                        let arg = match token {
-                            Token::Int(int) => Ok(Arg::Int(int)),
-                            _ => Err(ParserError::UnexpectedToken(Some(token))),
-                        }?;
-                        args.push(arg);
-                        let Some(Token::Coma) = self.peek() else {
-                            break;
-                        };
-                        self.pointer += 1;
+    fn parse_expression(&mut self) -> Result<Spanned<Expression>, Spanned<ParserError>> {
+        self.parse_binary(0)
+    }
+
+    fn parse_binary(
+        &mut self,
+        min_precedence: u8,
+    ) -> Result<Spanned<Expression>, Spanned<ParserError>> {
+        let mut left = self.parse_unary()?;
+        while let Some(op) = self.peek().as_ref().and_then(BinaryOp::from_token) {
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.pointer += 1;
+            let right = self.parse_binary(op.precedence() + 1)?;
+            let span = left.span.to(right.span);
+            left = Spanned::new(
+                Expression::Binary(Box::new(left), op, Box::new(right)),
+                span,
+            );
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Spanned<Expression>, Spanned<ParserError>> {
+        let op = match self.peek() {
+            Some(Token::Minus) => UnaryOp::Neg,
+            Some(Token::Bang) => UnaryOp::Not,
+            _ => return self.parse_primary(),
+        };
+        let start = self.span();
+        self.pointer += 1;
+        // Negative literals are folded so that the smallest Int, whose magnitude does not fit a
+        // positive one, can still be written
+        if let (UnaryOp::Neg, Some(Token::Int(magnitude))) = (op, self.peek()) {
+            let span = start.to(self.span());
+            let int = 0i64.checked_sub_unsigned(magnitude).ok_or_else(|| {
+                Spanned::new(ParserError::IntOutOfRange(format!("-{}", magnitude)), span)
+            })?;
+            self.pointer += 1;
+            return Ok(Spanned::new(Expression::Literal(Literal::Int(int)), span));
+        }
+        let operand = self.parse_unary()?;
+        let span = start.to(operand.span);
+        Ok(Spanned::new(Expression::Unary(op, Box::new(operand)), span))
+    }
+
+    fn parse_primary(&mut self) -> Result<Spanned<Expression>, Spanned<ParserError>> {
+        let token = self
+            .peek()
+            .ok_or_else(|| self.error(ParserError::ExpectedToken))?;
+        let start = self.span();
+        let literal = match token {
+            Token::Keyword(_) => {
+                let instruction = self.parse_instruction()?;
+                let span = start.to(self.previous_span());
+                return Ok(Spanned::new(Expression::Instruction(instruction), span));
+            }
+            Token::OpenParen => {
+                self.pointer += 1;
+                let expression = self.parse_expression()?;
+                self.expect(Token::CloseParen)?;
+                return Ok(Spanned::new(
+                    expression.node,
+                    start.to(self.previous_span()),
+                ));
+            }
+            Token::Int(magnitude) => Literal::Int(i64::try_from(magnitude).map_err(|_| {
+                Spanned::new(ParserError::IntOutOfRange(magnitude.to_string()), start)
+            })?),
+            Token::Float(float) => Literal::Float(float),
+            Token::Bool(bool) => Literal::Bool(bool),
+            Token::Null => Literal::Null,
+            Token::String(string) => Literal::String(string),
+            _ => return Err(self.error(ParserError::UnexpectedToken(Some(token)))),
+        };
+        self.pointer += 1;
+        Ok(Spanned::new(Expression::Literal(literal), start))
+    }
+
+    fn parse_instruction(&mut self) -> Result<Instruction, Spanned<ParserError>> {
+        let keyword = self.parse_keyword()?;
+        match self.peek() {
+            Some(Token::OpenParen) => {
+                self.pointer += 1;
+                let mut args: Vec<Spanned<Expression>> = vec![];
+                while let Some(token) = self.peek() {
+                    if let Token::CloseParen = token {
+                        break;
                     }
-                    let Some(Token::CloseParen) = self.peek() else {
-                        return Err(ParserError::Expected(Token::CloseParen, self.peek()));
+                    args.push(self.parse_expression()?);
+                    let Some(Token::Coma) = self.peek() else {
+                        break;
                     };
                     self.pointer += 1;
-                    Ok(Instruction::FunctionCall(keyword, args))
                 }
-                Some(Token::Dot) => {
-                    self.pointer += 1;
-                    Ok(Instruction::Access(
-                        keyword,
-                        Some(Box::new(self.parse_instruction()?)),
-                    ))
-                }
-                None => Ok(Instruction::Access(keyword, None)),
-                _ => Err(ParserError::UnexpectedToken(self.peek())),
-            },
-            _ => Err(ParserError::UnexpectedToken(Some(token))),
+                self.expect(Token::CloseParen)?;
+                let next = match self.peek() {
+                    Some(Token::Dot) => {
+                        self.pointer += 1;
+                        Some(Box::new(self.parse_instruction()?))
+                    }
+                    _ => None,
+                };
+                Ok(Instruction::FunctionCall(keyword, args, next))
+            }
+            Some(Token::Dot) => {
+                self.pointer += 1;
+                Ok(Instruction::Access(
+                    keyword,
+                    Some(Box::new(self.parse_instruction()?)),
+                ))
+            }
+            _ => Ok(Instruction::Access(keyword, None)),
         }
     }
 }
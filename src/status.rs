@@ -0,0 +1,88 @@
+use crate::{
+    game_object::StatusField,
+    term::{Style, StyledSpan},
+};
+use crossterm::style::Color;
+use std::collections::{HashMap, VecDeque};
+
+/// Number of ticks resource rates are averaged over.
+const RATE_WINDOW: usize = 10;
+
+/// Fields declared by the game objects, along with the recent values of every resource.
+#[derive(Clone, Default)]
+pub struct StatusBar {
+    fields: Vec<StatusField>,
+    history: HashMap<String, VecDeque<i64>>,
+}
+
+impl StatusBar {
+    /// Takes the fields of a new tick, recording the resources' values for their rates.
+    pub fn tick(&mut self, fields: Vec<StatusField>) {
+        self.history
+            .retain(|name, _| fields.iter().any(|field| field_name(field) == name));
+        for field in &fields {
+            if let StatusField::Resource(name, amount) = field {
+                let values = self.history.entry(name.clone()).or_default();
+                values.push_back(*amount);
+                if values.len() > RATE_WINDOW {
+                    values.pop_front();
+                }
+            }
+        }
+        self.fields = fields;
+    }
+
+    /// Takes fields read in between ticks, e.g. after a command, without changing the rates.
+    pub fn refresh(&mut self, fields: Vec<StatusField>) {
+        self.fields = fields;
+    }
+
+    /// Average change per tick of a resource, ticks being a second apart.
+    fn rate(&self, name: &str) -> Option<f64> {
+        let values = self.history.get(name)?;
+        if values.len() < 2 {
+            return None;
+        }
+        Some((values[values.len() - 1] - values[0]) as f64 / (values.len() - 1) as f64)
+    }
+
+    pub fn spans(&self) -> Vec<StyledSpan> {
+        let mut spans = vec![];
+        for field in &self.fields {
+            if !spans.is_empty() {
+                spans.push(StyledSpan::new(" │ ", Style::fg(Color::DarkGrey)));
+            }
+            match field {
+                StatusField::Resource(name, amount) => {
+                    spans.push(StyledSpan::plain(format!("{} ", name)));
+                    spans.push(StyledSpan::new(amount.to_string(), Style::default().bold()));
+                    match self.rate(name) {
+                        Some(rate) if rate > 0.0 => spans.push(StyledSpan::new(
+                            format!(" {:+.1}/s", rate),
+                            Style::fg(Color::Green),
+                        )),
+                        Some(rate) if rate < 0.0 => spans.push(StyledSpan::new(
+                            format!(" {:+.1}/s", rate),
+                            Style::fg(Color::Red),
+                        )),
+                        _ => (),
+                    }
+                }
+                StatusField::Timer(name, left) => {
+                    spans.push(StyledSpan::plain(format!("{} ", name)));
+                    spans.push(StyledSpan::new(
+                        format!("{}s", left),
+                        Style::fg(Color::Yellow),
+                    ));
+                }
+            }
+        }
+        spans
+    }
+}
+
+fn field_name(field: &StatusField) -> &str {
+    match field {
+        StatusField::Resource(name, _) | StatusField::Timer(name, _) => name,
+    }
+}
@@ -1,7 +1,10 @@
 use crossterm::{
     cursor,
-    event::{poll, read, DisableMouseCapture, EnableMouseCapture, Event},
-    style,
+    event::{
+        poll, read, DisableMouseCapture, EnableMouseCapture, Event, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    style::{self, Attribute, Color, ContentStyle},
     terminal::{self, disable_raw_mode, enable_raw_mode},
     ExecutableCommand, QueueableCommand,
 };
@@ -11,20 +14,275 @@ use std::{
     time::Duration,
     vec,
 };
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
+/// Colors and attributes of a piece of text, unset ones keeping the terminal's defaults.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub underline: bool,
+    pub reverse: bool,
+}
+
+impl Style {
+    pub fn fg(color: Color) -> Self {
+        Self {
+            fg: Some(color),
+            ..Self::default()
+        }
+    }
+
+    pub fn bg(self, color: Color) -> Self {
+        Self {
+            bg: Some(color),
+            ..self
+        }
+    }
+
+    pub fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    pub fn underline(self) -> Self {
+        Self {
+            underline: true,
+            ..self
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        Self {
+            reverse: true,
+            ..self
+        }
+    }
+
+    fn content_style(self) -> ContentStyle {
+        let mut content_style = ContentStyle::new();
+        content_style.foreground_color = self.fg;
+        content_style.background_color = self.bg;
+        if self.bold {
+            content_style.attributes.set(Attribute::Bold);
+        }
+        if self.underline {
+            content_style.attributes.set(Attribute::Underlined);
+        }
+        if self.reverse {
+            content_style.attributes.set(Attribute::Reverse);
+        }
+        content_style
+    }
+}
+
+/// Text printed with a single style, lines being made of several of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyledSpan {
+    pub text: String,
+    pub style: Style,
+}
+
+impl StyledSpan {
+    pub fn new(text: impl Into<String>, style: Style) -> Self {
+        Self {
+            text: text.into(),
+            style,
+        }
+    }
+
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self::new(text, Style::default())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WrapMode {
+    /// Wraps at the last grapheme that fits.
     Normal,
+    /// Only keeps the first line.
     Cut,
+    /// Wraps after the last whitespace that fits, or like `Normal` for words longer than a line.
+    Word,
+}
+
+/// Drops the first `cells` cells of `spans`, as when scrolled horizontally. A wide grapheme cut
+/// in half leaves a space in place of its visible half.
+pub fn skip_cells(spans: &[StyledSpan], cells: usize) -> Vec<StyledSpan> {
+    let mut skipped = 0;
+    let mut rest = vec![];
+    for span in spans {
+        if skipped >= cells {
+            rest.push(span.clone());
+            continue;
+        }
+        let mut text = String::new();
+        for grapheme in span.text.graphemes(true) {
+            if skipped >= cells {
+                text.push_str(grapheme);
+                continue;
+            }
+            skipped += grapheme.width();
+            if skipped > cells {
+                text.push_str(&" ".repeat(skipped - cells));
+            }
+        }
+        if !text.is_empty() {
+            rest.push(StyledSpan::new(text, span.style));
+        }
+    }
+    rest
+}
+
+/// Splits `spans` in the lines they take when printed `max_width` cells wide. Lines are split on
+/// graphemes by display width, so wide chars never straddle two lines.
+pub fn wrap_spans(
+    spans: &[StyledSpan],
+    max_width: u16,
+    wrap_mode: WrapMode,
+) -> Vec<Vec<StyledSpan>> {
+    let graphemes: Vec<(&str, Style)> = spans
+        .iter()
+        .flat_map(|span| {
+            span.text
+                .graphemes(true)
+                .map(move |grapheme| (grapheme, span.style))
+        })
+        .collect();
+
+    let mut lines = vec![];
+    let mut start = 0;
+    let mut width = 0;
+    let mut idx = 0;
+    while idx < graphemes.len() {
+        let grapheme_width = graphemes[idx].0.width();
+        if width + grapheme_width <= max_width as usize || width == 0 {
+            width += grapheme_width;
+            idx += 1;
+            continue;
+        }
+        let end = match wrap_mode {
+            WrapMode::Word => graphemes[start..idx]
+                .iter()
+                .rposition(|(grapheme, _)| grapheme.trim().is_empty())
+                .map_or(idx, |space| start + space + 1),
+            _ => idx,
+        };
+        lines.push(&graphemes[start..end]);
+        start = end;
+        // What was after the break moves to the next line
+        width = graphemes[start..idx]
+            .iter()
+            .map(|(grapheme, _)| grapheme.width())
+            .sum();
+    }
+    lines.push(&graphemes[start..]);
+    if wrap_mode == WrapMode::Cut {
+        lines.truncate(1);
+    }
+
+    lines
+        .into_iter()
+        .map(|line| {
+            // Consecutive graphemes of the same style are joined back in a single span
+            let mut spans: Vec<StyledSpan> = vec![];
+            for (grapheme, style) in line {
+                match spans.last_mut() {
+                    Some(last) if last.style == *style => last.text.push_str(grapheme),
+                    _ => spans.push(StyledSpan::new(*grapheme, *style)),
+                }
+            }
+            spans
+        })
+        .collect()
+}
+
+/// How long to wait for an event, keeping the main loop from spinning while idle.
+const POLL_TIMEOUT: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cell {
+    /// Grapheme shown in the cell, empty for the cells covered by a wide grapheme before them.
+    symbol: String,
+    style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            symbol: " ".to_string(),
+            style: Style::default(),
+        }
+    }
+}
+
+/// Grid of cells a frame is drawn into before being written to the terminal.
+#[derive(Debug, Clone, Default)]
+struct Buffer {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Buffer {
+    fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::default(); width as usize * height as usize],
+        }
+    }
+
+    fn idx(&self, x: u16, y: u16) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+
+    /// Puts `grapheme` at `x`, `y`, returning its width. Graphemes that don't fully fit are
+    /// dropped.
+    fn set(&mut self, x: u16, y: u16, grapheme: &str, style: Style) -> u16 {
+        let width = grapheme.width() as u16;
+        if width == 0 || y >= self.height || x + width > self.width {
+            return width;
+        }
+        let idx = self.idx(x, y);
+        self.cells[idx] = Cell {
+            symbol: grapheme.to_string(),
+            style,
+        };
+        for covered in &mut self.cells[idx + 1..idx + width as usize] {
+            *covered = Cell {
+                symbol: String::new(),
+                style,
+            };
+        }
+        width
+    }
 }
 
 pub struct Term {
     stdout: io::Stdout,
+    /// Whether the terminal reports modifiers on keys like Enter, needed for Shift+Enter.
+    keyboard_enhancement: bool,
+    /// Frame being drawn, written to the terminal on flush.
+    current: Buffer,
+    /// Frame currently shown by the terminal, so that only the cells that changed are written.
+    previous: Buffer,
+    /// Where the next write goes, and where the terminal's cursor is shown after a flush.
+    cursor: (u16, u16),
+    /// Size of the terminal, updated on resize events rather than queried every frame.
+    size: (u16, u16),
 }
 
 impl Clone for Term {
     fn clone(&self) -> Self {
         Self {
             stdout: io::stdout(),
+            keyboard_enhancement: self.keyboard_enhancement,
+            current: self.current.clone(),
+            previous: self.previous.clone(),
+            cursor: self.cursor,
+            size: self.size,
         }
     }
 }
@@ -33,6 +291,11 @@ impl Term {
     pub fn new() -> io::Result<Self> {
         let mut term = Self {
             stdout: io::stdout(),
+            keyboard_enhancement: false,
+            current: Buffer::default(),
+            previous: Buffer::default(),
+            cursor: (0, 0),
+            size: terminal::size()?,
         };
 
         term.enable()?;
@@ -43,6 +306,12 @@ impl Term {
     fn enable(&mut self) -> io::Result<&mut Self> {
         enable_raw_mode()?;
         self.stdout.execute(EnableMouseCapture)?;
+        self.keyboard_enhancement = terminal::supports_keyboard_enhancement().unwrap_or(false);
+        if self.keyboard_enhancement {
+            self.stdout.execute(PushKeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES,
+            ))?;
+        }
 
         self.clear()?.flush()?;
 
@@ -50,32 +319,96 @@ impl Term {
     }
 
     pub fn poll_event(&mut self) -> io::Result<Option<Event>> {
-        if poll(Duration::from_nanos(1))? {
+        if poll(POLL_TIMEOUT)? {
             return Ok(Some(read()?));
         }
         Ok(None)
     }
 
+    pub fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    /// Sets the size of the next frames, as given by a resize event.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.size = (width, height);
+    }
+
+    /// Starts a new frame, blank and the size of the terminal.
     pub fn clear(&mut self) -> io::Result<&mut Self> {
-        self.stdout
-            .queue(terminal::Clear(terminal::ClearType::All))?
-            .queue(terminal::Clear(terminal::ClearType::Purge))?
-            .queue(cursor::MoveTo(0, 0))?;
+        let (width, height) = self.size;
+        self.current = Buffer::new(width, height);
+        self.cursor = (0, 0);
         Ok(self)
     }
 
+    /// Writes the cells of the frame that changed since the last one to the terminal.
     pub fn flush(&mut self) -> io::Result<&mut Self> {
-        self.stdout.flush()?;
+        // Everything is written again when the size changed, as the terminal may have reflowed
+        let full = (self.previous.width, self.previous.height)
+            != (self.current.width, self.current.height);
+        if full {
+            self.stdout
+                .queue(terminal::Clear(terminal::ClearType::All))?
+                .queue(terminal::Clear(terminal::ClearType::Purge))?;
+        }
+
+        // Consecutive changed cells of the same style are written at once
+        let mut run = String::new();
+        let mut run_style = Style::default();
+        let mut run_end = None;
+        for y in 0..self.current.height {
+            for x in 0..self.current.width {
+                let idx = self.current.idx(x, y);
+                let cell = &self.current.cells[idx];
+                if (!full && *cell == self.previous.cells[idx]) || cell.symbol.is_empty() {
+                    continue;
+                }
+                if run_end != Some((x, y)) || run_style != cell.style {
+                    Self::write_run(&mut self.stdout, &mut run, run_style)?;
+                    self.stdout.queue(cursor::MoveTo(x, y))?;
+                    run_style = cell.style;
+                }
+                run.push_str(&cell.symbol);
+                run_end = Some((x + cell.symbol.width() as u16, y));
+            }
+        }
+        Self::write_run(&mut self.stdout, &mut run, run_style)?;
+
+        self.stdout
+            .queue(cursor::MoveTo(self.cursor.0, self.cursor.1))?
+            .flush()?;
+        self.previous = self.current.clone();
         Ok(self)
     }
 
+    fn write_run(stdout: &mut io::Stdout, run: &mut String, style: Style) -> io::Result<()> {
+        if run.is_empty() {
+            return Ok(());
+        }
+        if style == Style::default() {
+            stdout.queue(style::Print(&run))?;
+        } else {
+            stdout.queue(style::PrintStyledContent(style.content_style().apply(&run)))?;
+        }
+        run.clear();
+        Ok(())
+    }
+
     pub fn move_cursor(&mut self, x: u16, y: u16) -> io::Result<&mut Self> {
-        self.stdout.queue(cursor::MoveTo(x, y))?;
+        self.cursor = (x, y);
         Ok(self)
     }
 
     pub fn write(&mut self, str: &str) -> io::Result<&mut Self> {
-        self.stdout.queue(style::Print(str))?;
+        self.write_styled(str, Style::default())
+    }
+
+    pub fn write_styled(&mut self, str: &str, style: Style) -> io::Result<&mut Self> {
+        for grapheme in str.graphemes(true) {
+            let (x, y) = self.cursor;
+            self.cursor.0 = x.saturating_add(self.current.set(x, y, grapheme, style));
+        }
         Ok(self)
     }
 
@@ -84,29 +417,23 @@ impl Term {
         Ok(self)
     }
 
-    pub fn print_wrap(
+    /// Prints `spans` one after the other, wrapping them as a single line of text.
+    pub fn print_spans(
         &mut self,
-        str: &str,
+        spans: &[StyledSpan],
         x: u16,
         y: u16,
         max_width: u16,
         wrap_mode: WrapMode,
     ) -> io::Result<&mut Self> {
-        debug_assert!(max_width > 0, "max_width of print_wrap should be > 0");
-
-        str.chars()
-            .collect::<Vec<char>>()
-            .chunks(max_width as usize)
-            .enumerate()
-            .take(match wrap_mode {
-                WrapMode::Normal => usize::MAX,
-                WrapMode::Cut => 1,
-            })
-            .map(|(idx, segment)| -> io::Result<()> {
-                self.print(&segment.iter().collect::<String>(), x, y + idx as u16)?;
-                Ok(())
-            })
-            .collect::<io::Result<()>>()?;
+        debug_assert!(max_width > 0, "max_width of print_spans should be > 0");
+
+        for (idx, line) in wrap_spans(spans, max_width, wrap_mode).iter().enumerate() {
+            self.move_cursor(x, y + idx as u16)?;
+            for span in line {
+                self.write_styled(&span.text, span.style)?;
+            }
+        }
 
         Ok(self)
     }
@@ -120,12 +447,7 @@ impl Term {
         vertical: bool,
     ) -> io::Result<&mut Self> {
         if !vertical {
-            self.print(
-                &String::from_utf8(vec![char as u8; length as usize])
-                    .expect("Failed to create str, line"),
-                x,
-                y,
-            )?;
+            self.print(&char.to_string().repeat(length as usize), x, y)?;
         } else {
             for y_off in 0..length {
                 self.print(&char.to_string(), x, y + y_off)?;
@@ -134,18 +456,26 @@ impl Term {
         Ok(self)
     }
 
+    /// Draws a box with line drawing chars, `width` and `height` including the border.
     pub fn border_rect(
         &mut self,
-        char: char,
         x: u16,
         y: u16,
         width: u16,
         height: u16,
     ) -> io::Result<&mut Self> {
-        self.line(char, x, y, width, false)?
-            .line(char, x, y + height, width, false)?
-            .line(char, x, y, height, true)?
-            .line(char, x + width, y, height, true)?;
+        if width < 2 || height < 2 {
+            return Ok(self);
+        }
+        let (right, bottom) = (x + width - 1, y + height - 1);
+        self.line('─', x + 1, y, width - 2, false)?
+            .line('─', x + 1, bottom, width - 2, false)?
+            .line('│', x, y + 1, height - 2, true)?
+            .line('│', right, y + 1, height - 2, true)?
+            .print("┌", x, y)?
+            .print("┐", right, y)?
+            .print("└", x, bottom)?
+            .print("┘", right, bottom)?;
         Ok(self)
     }
 
@@ -164,7 +494,13 @@ impl Term {
     }
 
     fn disable(&mut self) -> io::Result<&mut Self> {
-        self.clear()?.flush()?.stdout.execute(DisableMouseCapture)?;
+        self.stdout
+            .queue(terminal::Clear(terminal::ClearType::All))?
+            .queue(cursor::MoveTo(0, 0))?
+            .execute(DisableMouseCapture)?;
+        if self.keyboard_enhancement {
+            self.stdout.execute(PopKeyboardEnhancementFlags)?;
+        }
         disable_raw_mode()?;
 
         Ok(self)